@@ -0,0 +1,12 @@
+-- Add migration script here
+ALTER TABLE otp_codes
+  ADD user_id BIGINT,
+  ADD purpose VARCHAR(50) NOT NULL DEFAULT 'password_reset',
+  ADD attempts INTEGER NOT NULL DEFAULT 0,
+  ADD used_at TIMESTAMP DEFAULT NULL,
+  ADD FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- Codes issued before this migration are not bound to anyone and can never be verified
+UPDATE otp_codes SET is_active = FALSE WHERE user_id IS NULL;
+
+CREATE INDEX otp_codes_user_id_purpose_idx ON otp_codes (user_id, purpose);
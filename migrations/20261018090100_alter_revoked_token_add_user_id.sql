@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE revoked_token
+  ADD user_id BIGINT,
+  ADD FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+CREATE INDEX revoked_token_user_id_idx ON revoked_token (user_id);
@@ -0,0 +1,20 @@
+-- Add migration script here
+CREATE TABLE refresh_tokens (
+  id BIGSERIAL PRIMARY KEY,
+  user_id BIGINT NOT NULL,
+  family_id UUID NOT NULL,
+  token_hash VARCHAR(64) NOT NULL UNIQUE,
+  is_used BOOLEAN NOT NULL DEFAULT FALSE,
+  is_revoked BOOLEAN NOT NULL DEFAULT FALSE,
+  expired_at TIMESTAMP NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX refresh_tokens_family_id_idx ON refresh_tokens (family_id);
+
+-- Access tokens remember the refresh family they were issued with
+ALTER TABLE revoked_token ADD family_id UUID DEFAULT NULL;
+
+CREATE INDEX revoked_token_family_id_idx ON revoked_token (family_id);
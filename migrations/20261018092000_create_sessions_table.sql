@@ -0,0 +1,34 @@
+-- Add migration script here
+CREATE TABLE sessions (
+  id UUID PRIMARY KEY,
+  user_id BIGINT NOT NULL,
+  user_agent TEXT,
+  ip_address VARCHAR(45),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_seen_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expired_at TIMESTAMP NOT NULL,
+  revoked_at TIMESTAMP DEFAULT NULL,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE INDEX sessions_user_id_idx ON sessions (user_id);
+
+-- Every existing refresh token family becomes a session
+INSERT INTO sessions (id, user_id, created_at, last_seen_at, expired_at, revoked_at)
+SELECT
+  family_id,
+  user_id,
+  MIN(created_at),
+  MAX(updated_at),
+  MAX(expired_at),
+  CASE WHEN BOOL_AND(is_revoked) THEN MAX(updated_at) END
+FROM refresh_tokens
+GROUP BY family_id, user_id;
+
+ALTER TABLE refresh_tokens RENAME COLUMN family_id TO session_id;
+ALTER INDEX refresh_tokens_family_id_idx RENAME TO refresh_tokens_session_id_idx;
+ALTER TABLE refresh_tokens
+  ADD FOREIGN KEY (session_id) REFERENCES sessions(id) ON DELETE CASCADE ON UPDATE CASCADE;
+
+-- Access tokens are now tracked through the session they carry in their jti claim
+DROP TABLE revoked_token;
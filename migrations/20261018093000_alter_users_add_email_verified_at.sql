@@ -0,0 +1,2 @@
+-- Add migration script here
+ALTER TABLE users ADD email_verified_at TIMESTAMP DEFAULT NULL;
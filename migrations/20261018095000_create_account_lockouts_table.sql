@@ -0,0 +1,25 @@
+-- Add migration script here
+CREATE TABLE account_lockouts (
+  user_id BIGINT PRIMARY KEY,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  lockout_count INTEGER NOT NULL DEFAULT 0,
+  locked_until TIMESTAMP DEFAULT NULL,
+  last_failed_at TIMESTAMP DEFAULT NULL,
+  updated_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+CREATE TABLE account_lockout_events (
+  id BIGSERIAL PRIMARY KEY,
+  user_id BIGINT NOT NULL,
+  event VARCHAR(20) NOT NULL,
+  failed_attempts INTEGER NOT NULL DEFAULT 0,
+  locked_until TIMESTAMP DEFAULT NULL,
+  ip_address VARCHAR(45),
+  actor_id BIGINT DEFAULT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  FOREIGN KEY (user_id) REFERENCES users(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (actor_id) REFERENCES users(id) ON DELETE SET NULL ON UPDATE CASCADE
+);
+
+CREATE INDEX account_lockout_events_user_id_idx ON account_lockout_events (user_id, created_at);
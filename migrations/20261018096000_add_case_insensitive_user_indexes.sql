@@ -0,0 +1,27 @@
+-- Add migration script here
+-- Refuse to continue while accounts differ only by letter case; they have to be merged by hand first
+DO $$
+DECLARE
+  duplicates TEXT;
+BEGIN
+  SELECT string_agg(format('%s %s (ids %s)', field, value, ids), '; ')
+  INTO duplicates
+  FROM (
+    SELECT 'username' AS field, LOWER(username) AS value, string_agg(id::TEXT, ', ' ORDER BY id) AS ids
+    FROM users GROUP BY LOWER(username) HAVING COUNT(*) > 1
+    UNION ALL
+    SELECT 'email', LOWER(email), string_agg(id::TEXT, ', ' ORDER BY id)
+    FROM users GROUP BY LOWER(email) HAVING COUNT(*) > 1
+  ) clashes;
+
+  IF duplicates IS NOT NULL THEN
+    RAISE EXCEPTION 'Users differ only by case, resolve before migrating: %', duplicates;
+  END IF;
+END $$;
+
+CREATE UNIQUE INDEX users_username_lower_key ON users (LOWER(username));
+CREATE UNIQUE INDEX users_email_lower_key ON users (LOWER(email));
+
+-- The case-insensitive indexes cover these
+ALTER TABLE users DROP CONSTRAINT users_username_key;
+ALTER TABLE users DROP CONSTRAINT users_email_key;
@@ -0,0 +1,38 @@
+-- Add migration script here
+CREATE TABLE permissions (
+  id SERIAL PRIMARY KEY,
+  name VARCHAR(100) UNIQUE NOT NULL,
+  description VARCHAR(255),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TABLE role_permissions (
+  role_id INTEGER NOT NULL,
+  permission_id INTEGER NOT NULL,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  PRIMARY KEY (role_id, permission_id),
+  FOREIGN KEY (role_id) REFERENCES roles(id) ON DELETE CASCADE ON UPDATE CASCADE,
+  FOREIGN KEY (permission_id) REFERENCES permissions(id) ON DELETE CASCADE ON UPDATE CASCADE
+);
+
+ALTER TABLE roles ADD CONSTRAINT roles_name_key UNIQUE (name);
+
+-- The seeded roles were inserted with explicit ids, so move the sequence past them
+SELECT setval(pg_get_serial_sequence('roles', 'id'), (SELECT MAX(id) FROM roles));
+
+INSERT INTO permissions (name, description) VALUES
+  ('role:manage', 'Manage roles and permission grants'),
+  ('user:manage', 'Manage user accounts'),
+  ('security:manage', 'Review lockouts and unlock accounts'),
+  ('hotel:read', 'View hotels and rooms'),
+  ('hotel:write', 'Create and edit hotels and rooms'),
+  ('order:read', 'View orders'),
+  ('order:write', 'Place and manage orders');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+JOIN permissions p ON
+  r.name = 'admin'
+  OR (r.name = 'tenant' AND p.name IN ('hotel:read', 'hotel:write', 'order:read'))
+  OR (r.name = 'customer' AND p.name IN ('hotel:read', 'order:read', 'order:write'));
@@ -0,0 +1,3 @@
+-- Add migration script here
+-- New address waiting for its verification code; the current one stays in use until then
+ALTER TABLE users ADD COLUMN pending_email VARCHAR(255);
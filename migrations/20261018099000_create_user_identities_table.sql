@@ -0,0 +1,25 @@
+-- Add migration script here
+-- Accounts at external OpenID Connect providers, several per user
+CREATE TABLE user_identities (
+  id BIGSERIAL PRIMARY KEY,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  provider VARCHAR(50) NOT NULL,
+  subject VARCHAR(255) NOT NULL,
+  email VARCHAR(255),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  last_login_at TIMESTAMP,
+  UNIQUE (provider, subject),
+  UNIQUE (user_id, provider)
+);
+
+-- Sign-ins that were sent to a provider and haven't come back yet
+CREATE TABLE oidc_login_states (
+  state_hash VARCHAR(64) PRIMARY KEY,
+  provider VARCHAR(50) NOT NULL,
+  nonce VARCHAR(64) NOT NULL,
+  code_verifier VARCHAR(128) NOT NULL,
+  -- Set when a signed-in user is linking another provider
+  link_user_id BIGINT REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expired_at TIMESTAMP NOT NULL
+);
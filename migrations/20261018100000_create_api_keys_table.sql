@@ -0,0 +1,20 @@
+-- Add migration script here
+-- Keys that integrations (e.g. a tenant's PMS) authenticate with instead of a user JWT
+CREATE TABLE api_keys (
+  id BIGSERIAL PRIMARY KEY,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON UPDATE CASCADE ON DELETE CASCADE,
+  name VARCHAR(100) NOT NULL,
+  -- Public part of the key, used to look it up and to tell keys apart
+  prefix VARCHAR(16) NOT NULL UNIQUE,
+  key_hash VARCHAR(64) NOT NULL,
+  scopes TEXT[] NOT NULL DEFAULT '{}',
+  -- Empty means every hotel the owner can reach
+  hotel_ids BIGINT[] NOT NULL DEFAULT '{}',
+  last_used_at TIMESTAMP,
+  last_used_ip VARCHAR(45),
+  expired_at TIMESTAMP,
+  revoked_at TIMESTAMP,
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX api_keys_user_id_idx ON api_keys (user_id);
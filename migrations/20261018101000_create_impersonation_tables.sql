@@ -0,0 +1,35 @@
+-- Add migration script here
+-- Sessions an admin opened to act as another user
+CREATE TABLE impersonations (
+  session_id UUID PRIMARY KEY,
+  actor_id BIGINT NOT NULL REFERENCES users(id) ON UPDATE CASCADE,
+  user_id BIGINT NOT NULL REFERENCES users(id) ON UPDATE CASCADE,
+  reason TEXT NOT NULL,
+  ip_address VARCHAR(45),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  expired_at TIMESTAMP NOT NULL
+);
+
+CREATE INDEX impersonations_actor_id_idx ON impersonations (actor_id);
+CREATE INDEX impersonations_user_id_idx ON impersonations (user_id);
+
+-- Every request made with an impersonation token
+CREATE TABLE impersonation_requests (
+  id BIGSERIAL PRIMARY KEY,
+  session_id UUID NOT NULL REFERENCES impersonations(session_id) ON UPDATE CASCADE,
+  method VARCHAR(10) NOT NULL,
+  path TEXT NOT NULL,
+  ip_address VARCHAR(45),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX impersonation_requests_session_id_idx ON impersonation_requests (session_id);
+
+INSERT INTO permissions (name, description) VALUES
+  ('user:impersonate', 'Act as another user to reproduce problems');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r
+JOIN permissions p ON p.name = 'user:impersonate'
+WHERE r.name = 'admin';
@@ -0,0 +1,6 @@
+-- Add migration script here
+ALTER TABLE users ADD COLUMN phone_verified_at TIMESTAMP;
+
+-- Store local Indonesian numbers in E.164, like new ones
+UPDATE users SET phone = '+62' || substr(phone, 2) WHERE phone ~ '^0[1-9][0-9]{6,12}$';
+UPDATE users SET phone = '+' || phone WHERE phone ~ '^62[1-9][0-9]{6,12}$';
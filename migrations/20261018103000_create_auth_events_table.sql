@@ -0,0 +1,63 @@
+-- Add migration script here
+-- Append-only trail of sign-ins, sign-outs and credential changes. No foreign keys:
+-- the trail has to outlive the accounts and sessions it mentions
+CREATE TABLE auth_events (
+  id BIGSERIAL PRIMARY KEY,
+  user_id BIGINT,
+  actor_id BIGINT,
+  event VARCHAR(32) NOT NULL,
+  outcome VARCHAR(16) NOT NULL,
+  -- Only kept for failures naming no known account
+  identifier VARCHAR(255),
+  detail TEXT,
+  ip_address VARCHAR(45),
+  user_agent VARCHAR(512),
+  created_at TIMESTAMP NOT NULL DEFAULT CURRENT_TIMESTAMP,
+  -- Set when the identifier, IP address and user agent were blanked
+  anonymized_at TIMESTAMP
+);
+
+CREATE INDEX auth_events_user_id_created_at_idx ON auth_events (user_id, created_at DESC);
+CREATE INDEX auth_events_created_at_idx ON auth_events (created_at DESC);
+
+-- Rows can't be changed or removed, except to blank their personal data once
+CREATE FUNCTION auth_events_append_only() RETURNS TRIGGER AS $$
+BEGIN
+  IF TG_OP = 'UPDATE'
+     AND OLD.anonymized_at IS NULL
+     AND NEW.anonymized_at IS NOT NULL
+     AND NEW.identifier IS NULL
+     AND NEW.ip_address IS NULL
+     AND NEW.user_agent IS NULL
+     AND (NEW.id, NEW.user_id, NEW.actor_id, NEW.event, NEW.outcome, NEW.detail, NEW.created_at)
+         IS NOT DISTINCT FROM (OLD.id, OLD.user_id, OLD.actor_id, OLD.event, OLD.outcome, OLD.detail, OLD.created_at)
+  THEN
+    RETURN NEW;
+  END IF;
+  RAISE EXCEPTION 'auth_events is append-only';
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER auth_events_no_update_or_delete
+BEFORE UPDATE OR DELETE ON auth_events
+FOR EACH ROW EXECUTE FUNCTION auth_events_append_only();
+
+CREATE TRIGGER auth_events_no_truncate
+BEFORE TRUNCATE ON auth_events
+FOR EACH STATEMENT EXECUTE FUNCTION auth_events_append_only();
+
+-- Blanks the personal data of a user's events, of failures naming one of the identifiers,
+-- or of everything older than a cutoff; used by account deletion and the retention purge
+CREATE FUNCTION anonymize_auth_events(p_user_id BIGINT, p_identifiers TEXT[], p_before TIMESTAMP)
+RETURNS BIGINT AS $$
+DECLARE
+  affected BIGINT;
+BEGIN
+  UPDATE auth_events
+  SET identifier = NULL, ip_address = NULL, user_agent = NULL, anonymized_at = CURRENT_TIMESTAMP
+  WHERE anonymized_at IS NULL
+    AND (user_id = p_user_id OR LOWER(identifier) = ANY(p_identifiers) OR created_at < p_before);
+  GET DIAGNOSTICS affected = ROW_COUNT;
+  RETURN affected;
+END;
+$$ LANGUAGE plpgsql;
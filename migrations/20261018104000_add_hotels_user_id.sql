@@ -0,0 +1,5 @@
+-- Add migration script here
+-- The tenant that manages the hotel; API keys can only be restricted to hotels their owner manages
+ALTER TABLE hotels ADD COLUMN user_id BIGINT REFERENCES users(id) ON UPDATE CASCADE;
+
+CREATE INDEX hotels_user_id_idx ON hotels (user_id);
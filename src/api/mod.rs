@@ -3,8 +3,14 @@ mod v1;
 use actix_web::web;
 
 pub fn register_urls(cfg: &mut actix_web::web::ServiceConfig) {
-    cfg.service(
+    cfg.configure(v1::routes::well_known::register_urls).service(
         web::scope("/api")
-            .service(web::scope("/v1").configure(v1::routes::auth::register_urls)),
+            .service(
+                web::scope("/v1")
+                    .configure(v1::routes::auth::register_urls)
+                    .configure(v1::routes::profile::register_urls)
+                    .configure(v1::routes::integration::register_urls)
+                    .configure(v1::routes::admin::register_urls),
+            ),
     );
 }
@@ -0,0 +1,90 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        extractors::auth::{AuthApiKey, AuthUser, RequireRole, Tenant},
+        models::{
+            api_key::CreateApiKeyPayload,
+            auth_event::{AuthEventKind, NewAuthEvent},
+            session::SessionMetadata,
+            StandardResponse,
+        },
+        services::{api_key::create_api_key_service, auth_event::record_auth_event},
+        validations::{api_key_validations::ApiKeyValidator, auth_validations::ValidationError},
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::{handle_database_error, handle_validation_error},
+};
+
+pub async fn list_api_keys(pool: web::Data<PostgresPool>, tenant: RequireRole<Tenant>) -> impl Responder {
+    let api_key_service = create_api_key_service(pool.get_ref().clone());
+    match api_key_service.list(tenant.id).await {
+        Ok(api_keys) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"api_keys": api_keys}),
+            Some("API keys retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List API Keys"),
+    }
+}
+
+pub async fn create_api_key(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<CreateApiKeyPayload>,
+    tenant: RequireRole<Tenant>,
+) -> impl Responder {
+    if let Err(e) = ApiKeyValidator::validate_create_api_key_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let api_key_service = create_api_key_service(pool.get_ref().clone());
+    match api_key_service.create(&tenant, &data).await {
+        //* The plain key is only ever shown here
+        Ok((api_key, key)) => HttpResponse::Created().json(StandardResponse::ok(
+            json!({"api_key": api_key, "key": key}),
+            Some("API key created successfully. Store it now; it won't be shown again.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn revoke_api_key(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i64>,
+    tenant: RequireRole<Tenant>,
+    req: HttpRequest,
+) -> impl Responder {
+    let key_id = path.into_inner();
+    let api_key_service = create_api_key_service(pool.get_ref().clone());
+    match api_key_service.revoke(tenant.id, key_id).await {
+        Ok(true) => {
+            let event = NewAuthEvent::success(AuthEventKind::TokenRevocation, SessionMetadata::from_request(&req))
+                .user(tenant.id)
+                .detail(format!("api key {}", key_id));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"message": "API key revoked."}),
+                Some("API key revoked successfully.".into()),
+            ))
+        }
+        Ok(false) => HttpResponse::NotFound().json(StandardResponse::<()>::error(
+            "API key not found".to_string(),
+            Some("NOT_FOUND".to_string()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Revoke API Key"),
+    }
+}
+
+/// Lets an integration check which key it is using and what that key may do.
+pub async fn current_api_key(user: AuthUser, api_key: AuthApiKey) -> impl Responder {
+    HttpResponse::Ok().json(StandardResponse::ok(
+        json!({
+            "api_key": api_key.0,
+            "owner": {"id": user.id, "username": user.username},
+        }),
+        Some("API key retrieved successfully.".into()),
+    ))
+}
@@ -1,41 +1,54 @@
-use actix_web::{dev::ServiceRequest, web, HttpRequest, HttpResponse, Responder};
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
 use serde_json::json;
 
 use crate::{
+    config::{PasswordSettings, SecuritySettings},
     domain::{
-        middlewares::auth::get_current_token,
+        errors::AppError,
+        extractors::auth::AuthClaims,
         models::{
-            auth::{ForgotPasswordPayload, LoginPayload, RegisterPayload},
+            auth::{
+                ForgotPasswordPayload, LoginAlertPayload, LoginOutcome, LoginPayload, MagicLinkLoginPayload, MagicLinkPayload,
+                OtpCheckPayload, RefreshTokenPayload, RegisterPayload, ResendVerificationPayload,
+                ResetPasswordPayload, VerifyEmailPayload,
+            },
+            auth_event::{AuthEventKind, NewAuthEvent},
+            session::SessionMetadata,
+            two_factor::TwoFactorLoginPayload,
             StandardResponse, User,
         },
-        services::{auth::create_auth_service, token::create_token_service, user::create_user_service},
+        services::{
+            auth::create_auth_service, auth_event::record_auth_event, session::create_session_service,
+            token::create_token_service, two_factor::create_two_factor_service,
+            user::create_user_service,
+        },
         validations::auth_validations::{AuthValidator, ValidationError},
     },
     infrastructure::{database::PostgresPool, email::test_smtp_connection},
-    shared::utils::error_helpers::{
-        handle_database_error, handle_error, handle_invalid_credentials, handle_validation_error,
+    shared::utils::{
+        error_helpers::{handle_database_error, handle_validation_error, unique_violation_field},
+        token_signing::TokenCodec,
     },
 };
 
 pub async fn register(
     pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    passwords: web::Data<PasswordSettings>,
     user_data: web::Json<RegisterPayload>,
+    req: HttpRequest,
 ) -> impl Responder {
     //* Check if form submitted is valid
-    if let Err(e) = AuthValidator::validate_register_payload(&user_data) {
+    if let Err(e) = AuthValidator::validate_register_payload(&user_data, &passwords) {
         return match e {
             ValidationError::Single(error) => handle_validation_error(vec![error]),
             ValidationError::Multiple(errors) => handle_validation_error(errors),
         };
     }
-    //*  Check if user with the same email or username already exists
+    //*  Check if user with the same username already exists, ignoring case
     let user_service = create_user_service(pool.get_ref().clone());
     match user_service.find_by("username", &user_data.username).await {
-        Ok(Some(_)) => {
-            return handle_validation_error(vec![
-                "User with this username or email already exists".into()
-            ])
-        }
+        Ok(Some(_)) => return AppError::Conflict("Username".into()).error_response(),
         Ok(None) => {}
         Err(e) => {
             return handle_database_error::<User>(e, "Find Existing User");
@@ -44,23 +57,39 @@ pub async fn register(
 
     //* Check if user with the same email already exists
     match user_service.find_by("email", &user_data.email).await {
-        Ok(Some(_)) => {
-            return handle_validation_error(vec!["User with this email already exists".into()])
-        }
+        Ok(Some(_)) => return AppError::Conflict("Email".into()).error_response(),
         Ok(None) => {}
         Err(e) => {
             return handle_database_error::<User>(e, "Find Existing User");
         }
     }
 
-    match user_service.create(&user_data).await {
-        Ok((user, token)) => {
+    let metadata = SessionMetadata::from_request(&req);
+    match user_service.create(&codec, &passwords, &user_data, &metadata).await {
+        Ok((user, tokens)) => {
+            //* A failed verification email shouldn't undo the registration; it can be resent
+            let auth_service = create_auth_service(pool.get_ref().clone());
+            let verification = ResendVerificationPayload {
+                email: user.email.clone(),
+            };
+            if let Err(e) = auth_service.send_email_verification(&verification).await {
+                log::error!("Failed to send verification email on register: {:?}", e);
+            }
             return HttpResponse::Created().json(StandardResponse::ok(
-                json!({"profile": user, "token": &token}),
+                json!({
+                    "profile": user,
+                    "token": &tokens.access_token,
+                    "refresh_token": &tokens.refresh_token,
+                    "expires_in": tokens.expires_in,
+                }),
                 Some("User created successfully.".into()),
             ))
         }
         Err(e) => {
+            //* Lost a race with another registration for the same name
+            if let Some(field) = unique_violation_field(&e) {
+                return AppError::Conflict(field.into()).error_response();
+            }
             return handle_database_error::<User>(e, "Create User");
         }
     }
@@ -68,7 +97,11 @@ pub async fn register(
 
 pub async fn login(
     pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    security: web::Data<SecuritySettings>,
+    passwords: web::Data<PasswordSettings>,
     login_data: web::Json<LoginPayload>,
+    req: HttpRequest,
 ) -> impl Responder {
     //* Check if form submitted is valid
     if let Err(e) = AuthValidator::validate_login_payload(&login_data) {
@@ -79,39 +112,84 @@ pub async fn login(
     }
     //* Check if user exists
     let user_service = create_user_service(pool.get_ref().clone());
-    match user_service.login(&login_data).await {
-        Ok((user, token)) => {
-            return HttpResponse::Ok().json(StandardResponse::ok(
-                json!({"profile": user, "token": &token}),
+    let metadata = SessionMetadata::from_request(&req);
+    match user_service.login(&codec, &security, &passwords, &login_data, &metadata).await {
+        Ok(LoginOutcome::Authenticated(user, tokens)) => {
+            let event = NewAuthEvent::success(AuthEventKind::Login, metadata).user(user.id).detail("password");
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "profile": user,
+                    "token": &tokens.access_token,
+                    "refresh_token": &tokens.refresh_token,
+                    "expires_in": tokens.expires_in,
+                }),
                 Some("User logged in successfully.".into()),
             ))
         }
-        Err(e) => match e {
-            sqlx::Error::RowNotFound => {
-                return handle_invalid_credentials();
-            }
-            _ => {
-                return handle_database_error::<User>(e, "Login User");
-            }
-        },
+        //* Not signed in yet; the second factor records the outcome
+        Ok(LoginOutcome::TwoFactorRequired(challenge)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "two_factor_required": true,
+                "challenge_token": &challenge.challenge_token,
+                "expires_in": challenge.expires_in,
+            }),
+            Some("Two-factor authentication required.".into()),
+        )),
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::Login, metadata)
+                .identifier(&login_data.identifier)
+                .detail(format!("password: {}", e));
+            record_auth_event(&pool, event).await;
+            e.error_response()
+        }
     }
 }
 
-pub async fn logout(pool: web::Data<PostgresPool>, req: HttpRequest) -> impl Responder {
-    // Extract token from request extensions (set by Authorization middleware)
-    let token = match get_current_token(&ServiceRequest::from_request(req)) {
-        Some(token) => token,
-        None => {
-            return HttpResponse::Unauthorized().json(StandardResponse::<()>::error(
-                "No valid token found".to_string(),
-                Some("UNAUTHORIZED".to_string()),
-            ));
+pub async fn login_two_factor(
+    pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    security: web::Data<SecuritySettings>,
+    data: web::Json<TwoFactorLoginPayload>,
+    req: HttpRequest,
+) -> impl Responder {
+    if data.challenge_token.is_empty() || data.code.is_empty() {
+        return handle_validation_error(vec!["Challenge token and code are required".into()]);
+    }
+
+    let two_factor_service = create_two_factor_service(pool.get_ref().clone());
+    let metadata = SessionMetadata::from_request(&req);
+    match two_factor_service.complete_login(&codec, &security, &data, &metadata).await {
+        Ok((user, tokens)) => {
+            let event = NewAuthEvent::success(AuthEventKind::Login, metadata).user(user.id).detail("two_factor");
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "profile": user,
+                    "token": &tokens.access_token,
+                    "refresh_token": &tokens.refresh_token,
+                    "expires_in": tokens.expires_in,
+                }),
+                Some("User logged in successfully.".into()),
+            ))
+        }
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::Login, metadata).detail(format!("two_factor: {}", e));
+            record_auth_event(&pool, event).await;
+            e.error_response()
         }
-    };
+    }
+}
 
-    let token_service = create_token_service(pool.get_ref().clone());
-    match token_service.revoke_token(&token).await {
+pub async fn logout(pool: web::Data<PostgresPool>, claims: AuthClaims, req: HttpRequest) -> impl Responder {
+    let session_service = create_session_service(pool.get_ref().clone());
+    match session_service.revoke(claims.id, claims.jti).await {
         Ok(_) => {
+            let event = NewAuthEvent::success(AuthEventKind::Logout, SessionMetadata::from_request(&req))
+                .user(claims.id)
+                .actor(claims.act.as_ref().map(|actor| actor.sub))
+                .detail(format!("session {}", claims.jti));
+            record_auth_event(&pool, event).await;
             return HttpResponse::Ok().json(StandardResponse::ok(
                 json!({"message": "Logged out successfully."}),
                 Some("Token revoked successfully.".into()),
@@ -123,18 +201,235 @@ pub async fn logout(pool: web::Data<PostgresPool>, req: HttpRequest) -> impl Res
     }
 }
 
+pub async fn refresh(
+    pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    data: web::Json<RefreshTokenPayload>,
+    req: HttpRequest,
+) -> impl Responder {
+    if data.refresh_token.is_empty() {
+        return handle_validation_error(vec!["Refresh token is required".into()]);
+    }
+
+    let token_service = create_token_service(pool.get_ref().clone());
+    let metadata = SessionMetadata::from_request(&req);
+    match token_service.refresh(&codec, &data.refresh_token, &metadata).await {
+        Ok((user, tokens)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "profile": user,
+                "token": &tokens.access_token,
+                "refresh_token": &tokens.refresh_token,
+                "expires_in": tokens.expires_in,
+            }),
+            Some("Token refreshed successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
 pub async fn forgot_password(
     pool: web::Data<PostgresPool>,
+    security: web::Data<SecuritySettings>,
     data: web::Json<ForgotPasswordPayload>,
 ) -> impl Responder {
+    if data.email.is_empty() {
+        return handle_validation_error(vec!["Email is required".into()]);
+    }
+
     let auth_service = create_auth_service(pool.get_ref().clone());
-    match auth_service.forgot_password(&data).await {
+    match auth_service.forgot_password(&security, &data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"message": "If the address is registered, a reset code has been sent."}),
+            Some("Email sent successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn check_otp(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<OtpCheckPayload>,
+) -> impl Responder {
+    if let Err(e) = AuthValidator::validate_otp_check_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    match auth_service.check_otp(&data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"valid": true}),
+            Some("OTP is valid.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn reset_password(
+    pool: web::Data<PostgresPool>,
+    passwords: web::Data<PasswordSettings>,
+    data: web::Json<ResetPasswordPayload>,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(e) = AuthValidator::validate_reset_password_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    let metadata = SessionMetadata::from_request(&req);
+    match auth_service.reset_password(&passwords, &data).await {
         Ok(_) => {
-            return HttpResponse::Ok().json(StandardResponse::ok(json!({"message": "Email sent successfully."}), Some("Email sent successfully.".into())));
+            let event = NewAuthEvent::success(AuthEventKind::PasswordReset, metadata).identifier(&data.email);
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"message": "Password has been reset. Please log in again."}),
+                Some("Password reset successfully.".into()),
+            ))
+        }
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::PasswordReset, metadata)
+                .identifier(&data.email)
+                .detail(e.to_string());
+            record_auth_event(&pool, event).await;
+            e.error_response()
         }
-        Err(e) => { 
-            return handle_error::<()>(e, "Forgot Password");
+    }
+}
+
+pub async fn verify_email(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<VerifyEmailPayload>,
+) -> impl Responder {
+    if let Err(e) = AuthValidator::validate_verify_email_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    match auth_service.verify_email(&data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"verified": true}),
+            Some("Email verified successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn resend_email_verification(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<ResendVerificationPayload>,
+) -> impl Responder {
+    if data.email.is_empty() {
+        return handle_validation_error(vec!["Email is required".into()]);
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    match auth_service.send_email_verification(&data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"message": "If the address needs verification, a new code has been sent."}),
+            Some("Verification email requested.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn request_magic_link(
+    pool: web::Data<PostgresPool>,
+    security: web::Data<SecuritySettings>,
+    data: web::Json<MagicLinkPayload>,
+) -> impl Responder {
+    if data.email.is_empty() {
+        return handle_validation_error(vec!["Email is required".into()]);
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    match auth_service.send_magic_link(&security, &data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"message": "If an account exists for this address, a sign in link has been sent."}),
+            Some("Sign in link requested.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn login_magic_link(
+    pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    security: web::Data<SecuritySettings>,
+    data: web::Json<MagicLinkLoginPayload>,
+    req: HttpRequest,
+) -> impl Responder {
+    if data.token.is_empty() {
+        return handle_validation_error(vec!["Token is required".into()]);
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    let metadata = SessionMetadata::from_request(&req);
+    match auth_service.login_with_magic_link(&codec, &security, &data, &metadata).await {
+        Ok(LoginOutcome::Authenticated(user, tokens)) => {
+            let event = NewAuthEvent::success(AuthEventKind::Login, metadata).user(user.id).detail("magic_link");
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "profile": user,
+                    "token": &tokens.access_token,
+                    "refresh_token": &tokens.refresh_token,
+                    "expires_in": tokens.expires_in,
+                }),
+                Some("User logged in successfully.".into()),
+            ))
+        }
+        Ok(LoginOutcome::TwoFactorRequired(challenge)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "two_factor_required": true,
+                "challenge_token": &challenge.challenge_token,
+                "expires_in": challenge.expires_in,
+            }),
+            Some("Two-factor authentication required.".into()),
+        )),
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::Login, metadata).detail(format!("magic_link: {}", e));
+            record_auth_event(&pool, event).await;
+            e.error_response()
+        }
+    }
+}
+
+/// The "this wasn't me" link from a login alert: signs the account out everywhere and
+/// emails a password reset code.
+pub async fn deny_login(
+    pool: web::Data<PostgresPool>,
+    security: web::Data<SecuritySettings>,
+    data: web::Json<LoginAlertPayload>,
+    req: HttpRequest,
+) -> impl Responder {
+    if data.token.is_empty() {
+        return handle_validation_error(vec!["Token is required".into()]);
+    }
+
+    let auth_service = create_auth_service(pool.get_ref().clone());
+    match auth_service.deny_login(&security, &data).await {
+        Ok((user, revoked)) => {
+            let event = NewAuthEvent::success(AuthEventKind::TokenRevocation, SessionMetadata::from_request(&req))
+                .user(user.id)
+                .detail(format!("unrecognised sign in reported, {} sessions", revoked));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "revoked_sessions": revoked,
+                    "message": "All sessions have been signed out. Check your email for a password reset code.",
+                }),
+                Some("Sign in reported successfully.".into()),
+            ))
         }
+        Err(e) => e.error_response(),
     }
 }
 
@@ -0,0 +1,46 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        extractors::auth::AuthUser,
+        models::{auth_event::AuthEventQuery, StandardResponse},
+        services::auth_event::create_auth_event_service,
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::handle_database_error,
+};
+
+pub async fn list_auth_events(
+    pool: web::Data<PostgresPool>,
+    query: web::Query<AuthEventQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(50).clamp(1, 200);
+
+    let auth_event_service = create_auth_event_service(pool.get_ref().clone());
+    match auth_event_service.list(&query, per_page, (page - 1) * per_page).await {
+        Ok((events, total)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "events": events,
+                "page": page,
+                "per_page": per_page,
+                "total": total,
+            }),
+            Some("Auth events retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Auth Events"),
+    }
+}
+
+/// The signed-in user's own sign-ins, sign-outs and credential changes, newest first.
+pub async fn recent_activity(pool: web::Data<PostgresPool>, user: AuthUser) -> impl Responder {
+    let auth_event_service = create_auth_event_service(pool.get_ref().clone());
+    match auth_event_service.recent_for_user(user.id, 50).await {
+        Ok(events) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"events": events}),
+            Some("Recent activity retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Recent Activity"),
+    }
+}
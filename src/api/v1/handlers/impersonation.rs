@@ -0,0 +1,82 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    config::SecuritySettings,
+    domain::{
+        extractors::auth::AuthUser,
+        models::{
+            impersonation::{ImpersonatePayload, ImpersonationQuery},
+            session::SessionMetadata,
+            StandardResponse,
+        },
+        services::impersonation::create_impersonation_service,
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::{
+        error_helpers::{handle_database_error, handle_validation_error},
+        token_signing::TokenCodec,
+    },
+};
+
+pub async fn impersonate_user(
+    req: HttpRequest,
+    pool: web::Data<PostgresPool>,
+    codec: web::Data<TokenCodec>,
+    security: web::Data<SecuritySettings>,
+    path: web::Path<i64>,
+    data: web::Json<ImpersonatePayload>,
+    admin: AuthUser,
+) -> impl Responder {
+    //* The reason ends up in the audit trail, so it is required
+    if data.reason.trim().is_empty() {
+        return handle_validation_error(vec!["Reason is required".into()]);
+    }
+
+    let metadata = SessionMetadata::from_request(&req);
+    let impersonation_service = create_impersonation_service(pool.get_ref().clone());
+    match impersonation_service
+        .start(&codec, &security, &admin, path.into_inner(), &data, &metadata)
+        .await
+    {
+        Ok((impersonation, token)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "token": token,
+                "expires_in": security.impersonation_minutes as i64 * 60,
+                "impersonation": impersonation,
+            }),
+            Some("Impersonation started successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn list_impersonations(
+    pool: web::Data<PostgresPool>,
+    query: web::Query<ImpersonationQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let impersonation_service = create_impersonation_service(pool.get_ref().clone());
+    match impersonation_service.list(&query, limit).await {
+        Ok(impersonations) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"impersonations": impersonations}),
+            Some("Impersonations retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Impersonations"),
+    }
+}
+
+pub async fn list_impersonation_requests(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<Uuid>,
+) -> impl Responder {
+    let impersonation_service = create_impersonation_service(pool.get_ref().clone());
+    match impersonation_service.list_requests(path.into_inner()).await {
+        Ok(requests) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"requests": requests}),
+            Some("Impersonated requests retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Impersonated Requests"),
+    }
+}
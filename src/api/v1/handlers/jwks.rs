@@ -0,0 +1,10 @@
+use actix_web::{http::header, web, HttpResponse, Responder};
+
+use crate::shared::utils::token_signing::TokenCodec;
+
+pub async fn jwks(codec: web::Data<TokenCodec>) -> impl Responder {
+    // Published in the raw JWK Set format so standard JWT libraries can consume it
+    HttpResponse::Ok()
+        .insert_header((header::CACHE_CONTROL, "public, max-age=300"))
+        .json(codec.jwks())
+}
@@ -0,0 +1,62 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        extractors::auth::AuthUser,
+        models::{lockout::LockoutEventQuery, StandardResponse},
+        services::lockout::create_lockout_service,
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::handle_database_error,
+};
+
+pub async fn get_lockout(pool: web::Data<PostgresPool>, path: web::Path<i64>) -> impl Responder {
+    let user_id = path.into_inner();
+    let lockout_service = create_lockout_service(pool.get_ref().clone());
+    let lockout = match lockout_service.find(user_id).await {
+        Ok(lockout) => lockout,
+        Err(e) => return handle_database_error::<()>(e, "Find Lockout"),
+    };
+    match lockout_service.list_events(Some(user_id), 20).await {
+        Ok(events) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"lockout": lockout, "events": events}),
+            Some("Lockout status retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Lockout Events"),
+    }
+}
+
+pub async fn unlock_account(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i64>,
+    admin: AuthUser,
+) -> impl Responder {
+    let lockout_service = create_lockout_service(pool.get_ref().clone());
+    match lockout_service.unlock(path.into_inner(), admin.id).await {
+        Ok(true) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"unlocked": true}),
+            Some("Account unlocked successfully.".into()),
+        )),
+        Ok(false) => HttpResponse::NotFound().json(StandardResponse::<()>::error(
+            "Account is not locked".to_string(),
+            Some("NOT_FOUND".to_string()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Unlock Account"),
+    }
+}
+
+pub async fn list_lockout_events(
+    pool: web::Data<PostgresPool>,
+    query: web::Query<LockoutEventQuery>,
+) -> impl Responder {
+    let limit = query.limit.unwrap_or(50).clamp(1, 200);
+    let lockout_service = create_lockout_service(pool.get_ref().clone());
+    match lockout_service.list_events(query.user_id, limit).await {
+        Ok(events) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"events": events}),
+            Some("Lockout events retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Lockout Events"),
+    }
+}
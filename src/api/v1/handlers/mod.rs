@@ -1 +1,13 @@
-pub mod auth;
\ No newline at end of file
+pub mod api_key;
+pub mod auth;
+pub mod auth_event;
+pub mod impersonation;
+pub mod jwks;
+pub mod lockout;
+pub mod oidc;
+pub mod permission;
+pub mod profile;
+pub mod role;
+pub mod session;
+pub mod two_factor;
+pub mod user;
\ No newline at end of file
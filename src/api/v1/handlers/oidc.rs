@@ -0,0 +1,153 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+
+use crate::{
+    config::{OidcSettings, SecuritySettings},
+    domain::{
+        errors::AppError,
+        extractors::auth::AuthUser,
+        models::{
+            auth::LoginOutcome,
+            auth_event::{AuthEventKind, NewAuthEvent},
+            oidc::{OidcCallbackQuery, OidcOutcome},
+            session::SessionMetadata,
+            StandardResponse,
+        },
+        services::{
+            auth::create_auth_service,
+            auth_event::record_auth_event,
+            oidc::{create_oidc_service, UNVERIFIED_ACCOUNT_CONFLICT},
+        },
+    },
+    infrastructure::{database::PostgresPool, oidc::OidcClient},
+    shared::utils::{error_helpers::handle_database_error, token_signing::TokenCodec},
+};
+
+pub async fn authorize(
+    pool: web::Data<PostgresPool>,
+    client: web::Data<OidcClient>,
+    settings: web::Data<OidcSettings>,
+    path: web::Path<String>,
+) -> impl Responder {
+    let oidc_service = create_oidc_service(pool.get_ref().clone());
+    match oidc_service.begin(&client, &settings, &path, None).await {
+        Ok(authorization_url) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"authorization_url": authorization_url}),
+            Some("Redirect the user to the provider to continue.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn callback(
+    pool: web::Data<PostgresPool>,
+    client: web::Data<OidcClient>,
+    codec: web::Data<TokenCodec>,
+    settings: web::Data<OidcSettings>,
+    path: web::Path<String>,
+    query: web::Query<OidcCallbackQuery>,
+    req: HttpRequest,
+) -> impl Responder {
+    let oidc_service = create_oidc_service(pool.get_ref().clone());
+    let metadata = SessionMetadata::from_request(&req);
+    match oidc_service
+        .complete(&client, &codec, &settings, &path, &query, &metadata)
+        .await
+    {
+        Ok(OidcOutcome::Login(LoginOutcome::Authenticated(user, tokens))) => {
+            //* Before the event is recorded, so this sign-in isn't already part of the history it's compared with
+            if let Some(security) = req.app_data::<web::Data<SecuritySettings>>() {
+                let auth_service = create_auth_service(pool.get_ref().clone());
+                if let Err(e) = auth_service.send_login_alert(security, &user, &metadata).await {
+                    log::error!("Failed to send login alert for user {}: {:?}", user.id, e);
+                }
+            }
+            let event = NewAuthEvent::success(AuthEventKind::Login, metadata)
+                .user(user.id)
+                .detail(format!("oidc:{}", path));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "profile": user,
+                    "token": &tokens.access_token,
+                    "refresh_token": &tokens.refresh_token,
+                    "expires_in": tokens.expires_in,
+                }),
+                Some("User logged in successfully.".into()),
+            ))
+        }
+        Ok(OidcOutcome::Login(LoginOutcome::TwoFactorRequired(challenge))) => {
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({
+                    "two_factor_required": true,
+                    "challenge_token": &challenge.challenge_token,
+                    "expires_in": challenge.expires_in,
+                }),
+                Some("Two-factor authentication required.".into()),
+            ))
+        }
+        Ok(OidcOutcome::Linked(identity)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"identity": identity}),
+            Some("Account linked successfully.".into()),
+        )),
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::Login, metadata).detail(format!("oidc:{}: {}", path, e));
+            record_auth_event(&pool, event).await;
+            match e {
+                AppError::Conflict(resource) if resource == UNVERIFIED_ACCOUNT_CONFLICT => {
+                    HttpResponse::Conflict().json(StandardResponse::<()>::error(
+                        format!(
+                            "An account with this email already exists. Sign in to it and link {} from your profile.",
+                            path
+                        ),
+                        Some("CONFLICT".to_string()),
+                    ))
+                }
+                e => e.error_response(),
+            }
+        }
+    }
+}
+
+pub async fn list_identities(pool: web::Data<PostgresPool>, user: AuthUser) -> impl Responder {
+    let oidc_service = create_oidc_service(pool.get_ref().clone());
+    match oidc_service.list_identities(user.id).await {
+        Ok(identities) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"identities": identities}),
+            Some("Linked accounts retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Identities"),
+    }
+}
+
+pub async fn link_identity(
+    pool: web::Data<PostgresPool>,
+    client: web::Data<OidcClient>,
+    settings: web::Data<OidcSettings>,
+    path: web::Path<String>,
+    user: AuthUser,
+) -> impl Responder {
+    let oidc_service = create_oidc_service(pool.get_ref().clone());
+    match oidc_service.begin(&client, &settings, &path, Some(user.id)).await {
+        Ok(authorization_url) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"authorization_url": authorization_url}),
+            Some("Redirect the user to the provider to link the account.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn unlink_identity(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<String>,
+    user: AuthUser,
+) -> impl Responder {
+    let oidc_service = create_oidc_service(pool.get_ref().clone());
+    match oidc_service.unlink(&user, &path).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"provider": path.into_inner()}),
+            Some("Account unlinked successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
@@ -0,0 +1,46 @@
+use actix_web::{web, HttpResponse, Responder};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        models::{permission::CreatePermissionPayload, StandardResponse},
+        services::permission::create_permission_service,
+        validations::{
+            auth_validations::ValidationError, role_validations::RoleValidator,
+        },
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::{handle_database_error, handle_validation_error},
+};
+
+pub async fn list_permissions(pool: web::Data<PostgresPool>) -> impl Responder {
+    let permission_service = create_permission_service(pool.get_ref().clone());
+    match permission_service.list().await {
+        Ok(permissions) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"permissions": permissions}),
+            Some("Permissions retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Permissions"),
+    }
+}
+
+pub async fn create_permission(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<CreatePermissionPayload>,
+) -> impl Responder {
+    if let Err(e) = RoleValidator::validate_create_permission_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let permission_service = create_permission_service(pool.get_ref().clone());
+    match permission_service.create(&data).await {
+        Ok(permission) => HttpResponse::Created().json(StandardResponse::ok(
+            json!({"permission": permission}),
+            Some("Permission created successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Create Permission"),
+    }
+}
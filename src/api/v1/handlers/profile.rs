@@ -0,0 +1,300 @@
+use actix_multipart::Multipart;
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use futures_util::TryStreamExt;
+use serde_json::json;
+
+use crate::{
+    config::{PasswordSettings, SmsSettings, UploadSettings},
+    domain::{
+        errors::AppError,
+        extractors::auth::{AuthClaims, AuthUser},
+        models::{
+            auth_event::{AuthEventKind, NewAuthEvent},
+            profile::{
+                ChangeEmailPayload, ChangePasswordPayload, ConfirmEmailChangePayload,
+                DeleteAccountPayload, SetPasswordPayload, UpdateProfilePayload, VerifyPhonePayload,
+            },
+            session::SessionMetadata,
+            StandardResponse,
+        },
+        services::{auth_event::record_auth_event, profile::create_profile_service},
+        validations::{auth_validations::ValidationError, profile_validations::ProfileValidator},
+    },
+    infrastructure::{database::PostgresPool, sms::SmsSender},
+    shared::utils::{
+        avatar::{process_avatar, thumbnail_url, AvatarError},
+        error_helpers::{handle_database_error, handle_validation_error},
+    },
+};
+
+pub async fn get_profile(user: AuthUser) -> impl Responder {
+    HttpResponse::Ok().json(StandardResponse::ok(
+        json!({"profile": user.0}),
+        Some("Profile retrieved successfully.".into()),
+    ))
+}
+
+pub async fn update_profile(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<UpdateProfilePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if let Err(e) = ProfileValidator::validate_update_profile_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.update(user.id, &data).await {
+        Ok(profile) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"profile": profile}),
+            Some("Profile updated successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Update Profile"),
+    }
+}
+
+pub async fn change_password(
+    pool: web::Data<PostgresPool>,
+    passwords: web::Data<PasswordSettings>,
+    data: web::Json<ChangePasswordPayload>,
+    user: AuthUser,
+    claims: AuthClaims,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(e) = ProfileValidator::validate_change_password_payload(&data, &passwords, &user) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service
+        .change_password(&passwords, &user, claims.jti, &data)
+        .await
+    {
+        Ok(revoked) => {
+            let event = NewAuthEvent::success(AuthEventKind::PasswordChange, SessionMetadata::from_request(&req))
+                .user(user.id)
+                .detail(format!("{} other sessions revoked", revoked));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"revoked_sessions": revoked}),
+                Some("Password changed successfully.".into()),
+            ))
+        }
+        Err(e) => {
+            let event = NewAuthEvent::failure(AuthEventKind::PasswordChange, SessionMetadata::from_request(&req))
+                .user(user.id)
+                .detail(e.to_string());
+            record_auth_event(&pool, event).await;
+            e.error_response()
+        }
+    }
+}
+
+/// Lets an account created through a sign-in provider add a password, which it then
+/// needs for email changes and account deletion.
+pub async fn set_password(
+    pool: web::Data<PostgresPool>,
+    passwords: web::Data<PasswordSettings>,
+    data: web::Json<SetPasswordPayload>,
+    user: AuthUser,
+    claims: AuthClaims,
+    req: HttpRequest,
+) -> impl Responder {
+    if let Err(e) = ProfileValidator::validate_set_password_payload(&data, &passwords, &user) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.set_password(&passwords, &user, claims.jti, &data).await {
+        Ok(_) => {
+            let event = NewAuthEvent::success(AuthEventKind::PasswordChange, SessionMetadata::from_request(&req))
+                .user(user.id)
+                .detail("first password set");
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"password_set": true}),
+                Some("Password set successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn change_email(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<ChangeEmailPayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if let Err(e) = ProfileValidator::validate_change_email_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.request_email_change(&user, &data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"pending_email": data.email}),
+            Some("A verification code has been sent to the new address.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn confirm_email_change(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<ConfirmEmailChangePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.otp.is_empty() {
+        return handle_validation_error(vec!["OTP is required".into()]);
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.confirm_email_change(&user, &data).await {
+        Ok(profile) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"profile": profile}),
+            Some("Email changed successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn send_phone_verification(
+    pool: web::Data<PostgresPool>,
+    sms: web::Data<dyn SmsSender>,
+    settings: web::Data<SmsSettings>,
+    user: AuthUser,
+) -> impl Responder {
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service
+        .send_phone_verification(sms.get_ref(), &settings, &user)
+        .await
+    {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"phone": user.phone}),
+            Some("Verification code sent successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn verify_phone(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<VerifyPhonePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.otp.is_empty() {
+        return handle_validation_error(vec!["OTP is required".into()]);
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.verify_phone(&user, &data).await {
+        Ok(profile) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"profile": profile}),
+            Some("Phone number verified successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+/// Reads the `avatar` part of the upload, giving up as soon as it exceeds `max_bytes`.
+async fn read_avatar_field(mut payload: Multipart, max_bytes: usize) -> Result<Option<Vec<u8>>, String> {
+    while let Some(mut field) = payload.try_next().await.map_err(|e| e.to_string())? {
+        if field.name() != Some("avatar") {
+            continue;
+        }
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.try_next().await.map_err(|e| e.to_string())? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(format!("Avatar must be at most {} KB", max_bytes / 1024));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+        return Ok(Some(bytes));
+    }
+    Ok(None)
+}
+
+pub async fn upload_avatar(
+    pool: web::Data<PostgresPool>,
+    uploads: web::Data<UploadSettings>,
+    payload: Multipart,
+    user: AuthUser,
+) -> impl Responder {
+    let bytes = match read_avatar_field(payload, uploads.avatar_max_bytes).await {
+        Ok(Some(bytes)) if !bytes.is_empty() => bytes,
+        Ok(_) => return handle_validation_error(vec!["Avatar file is required".into()]),
+        Err(error) => return handle_validation_error(vec![error]),
+    };
+
+    let avatar = match process_avatar(bytes, &uploads).await {
+        Ok(avatar) => avatar,
+        Err(AvatarError::UnsupportedFormat) => {
+            return handle_validation_error(vec!["Avatar must be a JPEG, PNG or WebP image".into()])
+        }
+        Err(AvatarError::Image(e)) => {
+            log::info!("Rejected avatar upload: {:?}", e);
+            return handle_validation_error(vec!["Avatar image could not be read".into()]);
+        }
+        Err(e) => {
+            log::error!("Failed to process avatar: {:?}", e);
+            return AppError::InternalServerError.error_response();
+        }
+    };
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.update_avatar(&uploads, &user, avatar).await {
+        Ok(profile) => {
+            let thumbnail = profile.image.as_deref().and_then(thumbnail_url);
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"profile": profile, "thumbnail": thumbnail}),
+                Some("Avatar updated successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn export_data(pool: web::Data<PostgresPool>, user: AuthUser) -> impl Responder {
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.export_data(&user).await {
+        Ok(archive) => HttpResponse::Ok()
+            .insert_header((
+                "Content-Disposition",
+                format!("attachment; filename=\"karcis-data-export-{}.json\"", user.id),
+            ))
+            .json(archive),
+        Err(e) => handle_database_error::<()>(e, "Export Data"),
+    }
+}
+
+pub async fn delete_account(
+    pool: web::Data<PostgresPool>,
+    uploads: web::Data<UploadSettings>,
+    data: web::Json<DeleteAccountPayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.password.is_empty() {
+        return handle_validation_error(vec!["Password is required".into()]);
+    }
+
+    let profile_service = create_profile_service(pool.get_ref().clone());
+    match profile_service.delete_account(&uploads, &user, &data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"deleted": true}),
+            Some("Account deleted successfully.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
@@ -0,0 +1,151 @@
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        errors::AppError,
+        models::{
+            role::{CreateRolePayload, RoleTwoFactorPayload},
+            StandardResponse,
+        },
+        services::{permission::create_permission_service, role::create_role_service},
+        validations::{
+            auth_validations::ValidationError, role_validations::RoleValidator,
+        },
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::{
+        error_helpers::{handle_database_error, handle_validation_error},
+        permission_cache::PermissionCache,
+    },
+};
+
+pub async fn list_roles(pool: web::Data<PostgresPool>) -> impl Responder {
+    let role_service = create_role_service(pool.get_ref().clone());
+    match role_service.list().await {
+        Ok(roles) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"roles": roles}),
+            Some("Roles retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Roles"),
+    }
+}
+
+pub async fn get_role(pool: web::Data<PostgresPool>, path: web::Path<i32>) -> impl Responder {
+    let role_id = path.into_inner();
+    let role_service = create_role_service(pool.get_ref().clone());
+    let role = match role_service.find(role_id).await {
+        Ok(Some(role)) => role,
+        Ok(None) => return AppError::NotFound("Role".into()).error_response(),
+        Err(e) => return handle_database_error::<()>(e, "Find Role"),
+    };
+
+    let permission_service = create_permission_service(pool.get_ref().clone());
+    match permission_service.for_role(role_id).await {
+        Ok(permissions) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"role": role, "permissions": permissions}),
+            Some("Role retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Role Permissions"),
+    }
+}
+
+pub async fn create_role(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<CreateRolePayload>,
+) -> impl Responder {
+    if let Err(e) = RoleValidator::validate_create_role_payload(&data) {
+        return match e {
+            ValidationError::Single(error) => handle_validation_error(vec![error]),
+            ValidationError::Multiple(errors) => handle_validation_error(errors),
+        };
+    }
+
+    let role_service = create_role_service(pool.get_ref().clone());
+    match role_service.create(&data.name).await {
+        Ok(role) => HttpResponse::Created().json(StandardResponse::ok(
+            json!({"role": role}),
+            Some("Role created successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Create Role"),
+    }
+}
+
+pub async fn delete_role(
+    pool: web::Data<PostgresPool>,
+    cache: web::Data<PermissionCache>,
+    path: web::Path<i32>,
+) -> impl Responder {
+    let role_id = path.into_inner();
+    let role_service = create_role_service(pool.get_ref().clone());
+    match role_service.delete(role_id).await {
+        Ok(_) => {
+            cache.invalidate(role_id);
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"deleted": true}),
+                Some("Role deleted successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn update_two_factor_requirement(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i32>,
+    data: web::Json<RoleTwoFactorPayload>,
+) -> impl Responder {
+    let role_service = create_role_service(pool.get_ref().clone());
+    match role_service
+        .set_two_factor_requirement(path.into_inner(), data.required)
+        .await
+    {
+        Ok(Some(role)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"role": role}),
+            Some("Role updated successfully.".into()),
+        )),
+        Ok(None) => HttpResponse::NotFound().json(StandardResponse::<()>::error(
+            "Role not found".to_string(),
+            Some("NOT_FOUND".to_string()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Update Role"),
+    }
+}
+
+pub async fn grant_permission(
+    pool: web::Data<PostgresPool>,
+    cache: web::Data<PermissionCache>,
+    path: web::Path<(i32, String)>,
+) -> impl Responder {
+    let (role_id, permission) = path.into_inner();
+    let permission_service = create_permission_service(pool.get_ref().clone());
+    match permission_service.grant(role_id, &permission).await {
+        Ok(_) => {
+            cache.invalidate(role_id);
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"role_id": role_id, "permission": permission, "granted": true}),
+                Some("Permission granted successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn revoke_permission(
+    pool: web::Data<PostgresPool>,
+    cache: web::Data<PermissionCache>,
+    path: web::Path<(i32, String)>,
+) -> impl Responder {
+    let (role_id, permission) = path.into_inner();
+    let permission_service = create_permission_service(pool.get_ref().clone());
+    match permission_service.revoke(role_id, &permission).await {
+        Ok(_) => {
+            cache.invalidate(role_id);
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"role_id": role_id, "permission": permission, "granted": false}),
+                Some("Permission revoked successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
@@ -0,0 +1,78 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        extractors::auth::AuthClaims,
+        models::{
+            auth_event::{AuthEventKind, NewAuthEvent},
+            session::SessionMetadata,
+            StandardResponse,
+        },
+        services::{auth_event::record_auth_event, session::create_session_service},
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::handle_database_error,
+};
+
+pub async fn list_sessions(pool: web::Data<PostgresPool>, claims: AuthClaims) -> impl Responder {
+    let session_service = create_session_service(pool.get_ref().clone());
+    match session_service.list_active(claims.id, claims.jti).await {
+        Ok(sessions) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"sessions": sessions}),
+            Some("Active sessions retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Sessions"),
+    }
+}
+
+pub async fn revoke_session(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<Uuid>,
+    claims: AuthClaims,
+    req: HttpRequest,
+) -> impl Responder {
+    let session_id = path.into_inner();
+    let session_service = create_session_service(pool.get_ref().clone());
+    match session_service.revoke(claims.id, session_id).await {
+        Ok(true) => {
+            let event = NewAuthEvent::success(AuthEventKind::TokenRevocation, SessionMetadata::from_request(&req))
+                .user(claims.id)
+                .actor(claims.act.as_ref().map(|actor| actor.sub))
+                .detail(format!("session {}", session_id));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"message": "Session revoked."}),
+                Some("Session revoked successfully.".into()),
+            ))
+        }
+        Ok(false) => HttpResponse::NotFound().json(StandardResponse::<()>::error(
+            "Session not found".to_string(),
+            Some("NOT_FOUND".to_string()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "Revoke Session"),
+    }
+}
+
+pub async fn revoke_other_sessions(
+    pool: web::Data<PostgresPool>,
+    claims: AuthClaims,
+    req: HttpRequest,
+) -> impl Responder {
+    let session_service = create_session_service(pool.get_ref().clone());
+    match session_service.revoke_others(claims.id, claims.jti).await {
+        Ok(count) => {
+            let event = NewAuthEvent::success(AuthEventKind::TokenRevocation, SessionMetadata::from_request(&req))
+                .user(claims.id)
+                .actor(claims.act.as_ref().map(|actor| actor.sub))
+                .detail(format!("{} other sessions", count));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"revoked": count}),
+                Some("Other sessions revoked successfully.".into()),
+            ))
+        }
+        Err(e) => handle_database_error::<()>(e, "Revoke Other Sessions"),
+    }
+}
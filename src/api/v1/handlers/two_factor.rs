@@ -0,0 +1,83 @@
+use actix_web::{web, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        extractors::auth::AuthUser,
+        models::{
+            two_factor::{TwoFactorCodePayload, TwoFactorDisablePayload},
+            StandardResponse,
+        },
+        services::two_factor::create_two_factor_service,
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::handle_validation_error,
+};
+
+pub async fn setup(pool: web::Data<PostgresPool>, user: AuthUser) -> impl Responder {
+    let two_factor_service = create_two_factor_service(pool.get_ref().clone());
+    match two_factor_service.setup(&user).await {
+        Ok(setup) => HttpResponse::Ok().json(StandardResponse::ok(
+            setup,
+            Some("Scan the provisioning URI and confirm with a code to enable 2FA.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn confirm(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<TwoFactorCodePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.code.is_empty() {
+        return handle_validation_error(vec!["Code is required".into()]);
+    }
+
+    let two_factor_service = create_two_factor_service(pool.get_ref().clone());
+    match two_factor_service.confirm(&user, &data.code).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"recovery_codes": recovery_codes}),
+            Some("Two-factor authentication enabled. Store the recovery codes somewhere safe.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn disable(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<TwoFactorDisablePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.password.is_empty() || data.code.is_empty() {
+        return handle_validation_error(vec!["Password and code are required".into()]);
+    }
+
+    let two_factor_service = create_two_factor_service(pool.get_ref().clone());
+    match two_factor_service.disable(&user, &data).await {
+        Ok(_) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"enabled": false}),
+            Some("Two-factor authentication disabled.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn regenerate_recovery_codes(
+    pool: web::Data<PostgresPool>,
+    data: web::Json<TwoFactorCodePayload>,
+    user: AuthUser,
+) -> impl Responder {
+    if data.code.is_empty() {
+        return handle_validation_error(vec!["Code is required".into()]);
+    }
+
+    let two_factor_service = create_two_factor_service(pool.get_ref().clone());
+    match two_factor_service.regenerate_recovery_codes(&user, &data.code).await {
+        Ok(recovery_codes) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"recovery_codes": recovery_codes}),
+            Some("Recovery codes regenerated.".into()),
+        )),
+        Err(e) => e.error_response(),
+    }
+}
@@ -0,0 +1,146 @@
+use actix_web::{web, HttpRequest, HttpResponse, Responder, ResponseError};
+use serde_json::json;
+
+use crate::{
+    domain::{
+        errors::AppError,
+        extractors::auth::AuthUser,
+        models::{
+            auth_event::{AuthEventKind, NewAuthEvent},
+            session::SessionMetadata,
+            user::{UpdateUserRolePayload, UserQuery},
+            StandardResponse,
+        },
+        services::{
+            auth_event::record_auth_event, session::create_session_service, user::create_user_service,
+        },
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::error_helpers::{handle_database_error, handle_validation_error},
+};
+
+pub async fn list_users(
+    pool: web::Data<PostgresPool>,
+    query: web::Query<UserQuery>,
+) -> impl Responder {
+    let page = query.page.unwrap_or(1).max(1);
+    let per_page = query.per_page.unwrap_or(20).clamp(1, 100);
+
+    let user_service = create_user_service(pool.get_ref().clone());
+    match user_service.list(&query, per_page, (page - 1) * per_page).await {
+        Ok((users, total)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({
+                "users": users,
+                "page": page,
+                "per_page": per_page,
+                "total": total,
+            }),
+            Some("Users retrieved successfully.".into()),
+        )),
+        Err(e) => handle_database_error::<()>(e, "List Users"),
+    }
+}
+
+pub async fn get_user(pool: web::Data<PostgresPool>, path: web::Path<i64>) -> impl Responder {
+    let user_service = create_user_service(pool.get_ref().clone());
+    match user_service.find(path.into_inner()).await {
+        Ok(Some(user)) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"user": user}),
+            Some("User retrieved successfully.".into()),
+        )),
+        Ok(None) => AppError::NotFound("User".into()).error_response(),
+        Err(e) => handle_database_error::<()>(e, "Find User"),
+    }
+}
+
+pub async fn update_user_role(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i64>,
+    data: web::Json<UpdateUserRolePayload>,
+    admin: AuthUser,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = path.into_inner();
+    //* Keeps an admin from locking themselves out of this API
+    if user_id == admin.id {
+        return handle_validation_error(vec!["You cannot change your own role".into()]);
+    }
+
+    let user_service = create_user_service(pool.get_ref().clone());
+    let previous_role_id = match user_service.find(user_id).await {
+        Ok(Some(user)) => user.role_id,
+        Ok(None) => return AppError::NotFound("User".into()).error_response(),
+        Err(e) => return handle_database_error::<()>(e, "Find User"),
+    };
+    match user_service.set_role(user_id, data.role_id).await {
+        Ok(user) => {
+            let event = NewAuthEvent::success(AuthEventKind::RoleChange, SessionMetadata::from_request(&req))
+                .user(user.id)
+                .actor(Some(admin.id))
+                .detail(format!("role {} -> {}", previous_role_id, user.role_id));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"user": user}),
+                Some("User role updated successfully.".into()),
+            ))
+        }
+        Err(e) => e.error_response(),
+    }
+}
+
+pub async fn delete_user(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i64>,
+    admin: AuthUser,
+) -> impl Responder {
+    let user_id = path.into_inner();
+    if user_id == admin.id {
+        return handle_validation_error(vec!["You cannot delete your own account".into()]);
+    }
+
+    let user_service = create_user_service(pool.get_ref().clone());
+    match user_service.soft_delete(user_id).await {
+        Ok(true) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"deleted": true}),
+            Some("User deleted successfully.".into()),
+        )),
+        Ok(false) => AppError::NotFound("User".into()).error_response(),
+        Err(e) => handle_database_error::<()>(e, "Delete User"),
+    }
+}
+
+pub async fn restore_user(pool: web::Data<PostgresPool>, path: web::Path<i64>) -> impl Responder {
+    let user_service = create_user_service(pool.get_ref().clone());
+    match user_service.restore(path.into_inner()).await {
+        Ok(true) => HttpResponse::Ok().json(StandardResponse::ok(
+            json!({"deleted": false}),
+            Some("User restored successfully.".into()),
+        )),
+        Ok(false) => AppError::NotFound("Deleted user".into()).error_response(),
+        Err(e) => handle_database_error::<()>(e, "Restore User"),
+    }
+}
+
+pub async fn force_logout(
+    pool: web::Data<PostgresPool>,
+    path: web::Path<i64>,
+    admin: AuthUser,
+    req: HttpRequest,
+) -> impl Responder {
+    let user_id = path.into_inner();
+    let session_service = create_session_service(pool.get_ref().clone());
+    match session_service.revoke_all(user_id).await {
+        Ok(count) => {
+            let event = NewAuthEvent::success(AuthEventKind::TokenRevocation, SessionMetadata::from_request(&req))
+                .user(user_id)
+                .actor(Some(admin.id))
+                .detail(format!("all {} sessions", count));
+            record_auth_event(&pool, event).await;
+            HttpResponse::Ok().json(StandardResponse::ok(
+                json!({"revoked": count}),
+                Some("User signed out of all sessions.".into()),
+            ))
+        }
+        Err(e) => handle_database_error::<()>(e, "Force Logout"),
+    }
+}
@@ -0,0 +1,97 @@
+use actix_web::web;
+
+use crate::api::v1::handlers::{auth_event, impersonation, lockout, permission, role, user};
+use crate::domain::middlewares::auth::Authorization;
+
+pub fn register_urls(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/admin")
+            .service(
+                web::scope("/roles")
+                    .wrap(Authorization::require_permission("role:manage"))
+                    .route("", web::get().to(role::list_roles))
+                    .route("", web::post().to(role::create_role))
+                    .route("/{role_id}", web::get().to(role::get_role))
+                    .route("/{role_id}", web::delete().to(role::delete_role))
+                    .route(
+                        "/{role_id}/two-factor",
+                        web::patch().to(role::update_two_factor_requirement),
+                    )
+                    .route(
+                        "/{role_id}/permissions/{permission}",
+                        web::put().to(role::grant_permission),
+                    )
+                    .route(
+                        "/{role_id}/permissions/{permission}",
+                        web::delete().to(role::revoke_permission),
+                    ),
+            )
+            .service(
+                web::resource("/permissions")
+                    .wrap(Authorization::require_permission("role:manage"))
+                    .route(web::get().to(permission::list_permissions))
+                    .route(web::post().to(permission::create_permission)),
+            )
+            .service(
+                web::resource("/lockout-events")
+                    .wrap(Authorization::require_permission("security:manage"))
+                    .route(web::get().to(lockout::list_lockout_events)),
+            )
+            .service(
+                web::resource("/auth-events")
+                    .wrap(Authorization::require_permission("security:manage"))
+                    .route(web::get().to(auth_event::list_auth_events)),
+            )
+            .service(
+                web::resource("/users")
+                    .wrap(Authorization::require_permission("user:manage"))
+                    .route(web::get().to(user::list_users)),
+            )
+            .service(
+                web::resource("/users/{user_id}")
+                    .wrap(Authorization::require_permission("user:manage"))
+                    .route(web::get().to(user::get_user))
+                    .route(web::delete().to(user::delete_user)),
+            )
+            .service(
+                web::resource("/users/{user_id}/role")
+                    .wrap(Authorization::require_permission("user:manage"))
+                    .route(web::patch().to(user::update_user_role)),
+            )
+            .service(
+                web::resource("/users/{user_id}/restore")
+                    .wrap(Authorization::require_permission("user:manage"))
+                    .route(web::post().to(user::restore_user)),
+            )
+            .service(
+                web::resource("/users/{user_id}/logout")
+                    .wrap(Authorization::require_permission("user:manage"))
+                    .route(web::post().to(user::force_logout)),
+            )
+            .service(
+                web::resource("/users/{user_id}/impersonate")
+                    .wrap(Authorization::require_permission("user:impersonate").deny_impersonation())
+                    .route(web::post().to(impersonation::impersonate_user)),
+            )
+            .service(
+                web::resource("/impersonations")
+                    .wrap(Authorization::require_permission("user:impersonate"))
+                    .route(web::get().to(impersonation::list_impersonations)),
+            )
+            .service(
+                web::resource("/impersonations/{session_id}/requests")
+                    .wrap(Authorization::require_permission("user:impersonate"))
+                    .route(web::get().to(impersonation::list_impersonation_requests)),
+            )
+            .service(
+                web::resource("/users/{user_id}/lockout")
+                    .wrap(Authorization::require_permission("security:manage"))
+                    .route(web::get().to(lockout::get_lockout)),
+            )
+            .service(
+                web::resource("/users/{user_id}/unlock")
+                    .wrap(Authorization::require_permission("security:manage"))
+                    .route(web::post().to(lockout::unlock_account)),
+            ),
+    );
+}
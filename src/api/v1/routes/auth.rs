@@ -1,38 +1,130 @@
 use actix_web::web;
 
-use crate::api::v1::handlers::auth;
-use crate::domain::middlewares::auth::Authorization;
+use crate::api::v1::handlers::{auth, oidc, session, two_factor};
+use crate::domain::middlewares::{auth::Authorization, rate_limit::RateLimit};
 
 pub fn register_urls(cfg: &mut web::ServiceConfig) {
     cfg.service(
         web::scope("/auth")
-            .route("/login", web::post().to(auth::login))
+            .route(
+                "/login",
+                web::post().to(auth::login).wrap(RateLimit::per_ip("login")),
+            )
+            .route(
+                "/login/2fa",
+                web::post()
+                    .to(auth::login_two_factor)
+                    .wrap(RateLimit::per_ip("login")),
+            )
+            .route(
+                "/oidc/{provider}/authorize",
+                web::get().to(oidc::authorize).wrap(RateLimit::per_ip("login")),
+            )
+            .route(
+                "/oidc/{provider}/callback",
+                web::get().to(oidc::callback).wrap(RateLimit::per_ip("login")),
+            )
+            .route(
+                "/magic-link",
+                web::post()
+                    .to(auth::request_magic_link)
+                    .wrap(RateLimit::per_ip("password-reset")),
+            )
+            //* The emailed link opens a frontend page that posts the token here, so mail
+            //* scanners following links can't use it up
+            .route(
+                "/magic-link/verify",
+                web::post()
+                    .to(auth::login_magic_link)
+                    .wrap(RateLimit::per_ip("login")),
+            )
+            //* POST for the same reason; the alert email's page submits the token
+            .route(
+                "/login-alert/deny",
+                web::post()
+                    .to(auth::deny_login)
+                    .wrap(RateLimit::per_ip("login")),
+            )
             .route("/register", web::post().to(auth::register))
+            .route("/refresh", web::post().to(auth::refresh))
             .route(
                 "/forgot-password",
-                web::post().to(auth::forgot_password),
+                web::post()
+                    .to(auth::forgot_password)
+                    .wrap(RateLimit::per_ip("password-reset")),
             )
             .route(
                 "/test-email",
                 web::get().to(auth::test_email_connection),
             )
-            // .route(
-            //     "/forgot-password/reset",
-            //     web::post().to(super::views::reset_password),
-            // )
-            // .route("/otp-check", web::post().to(super::views::check_otp))
+            .route(
+                "/forgot-password/reset",
+                web::post()
+                    .to(auth::reset_password)
+                    .wrap(RateLimit::per_ip("otp")),
+            )
+            .route(
+                "/otp-check",
+                web::post().to(auth::check_otp).wrap(RateLimit::per_ip("otp")),
+            )
+            .route(
+                "/verify-email",
+                web::post().to(auth::verify_email).wrap(RateLimit::per_ip("otp")),
+            )
+            .route(
+                "/verify-email/resend",
+                web::post()
+                    .to(auth::resend_email_verification)
+                    .wrap(RateLimit::per_ip("password-reset")),
+            )
             .route(
                 "/logout",
-                web::get().to(auth::logout).wrap(Authorization::require_user()),
+                web::get()
+                    .to(auth::logout)
+                    .wrap(Authorization::require_user().allow_pending_two_factor()),
+            )
+            .service(
+                web::scope("/2fa")
+                    .service(
+                        web::resource("/setup")
+                            .wrap(
+                                Authorization::require_authenticated()
+                                    .allow_pending_two_factor()
+                                    .deny_impersonation(),
+                            )
+                            .route(web::post().to(two_factor::setup)),
+                    )
+                    .service(
+                        web::resource("/confirm")
+                            .wrap(
+                                Authorization::require_authenticated()
+                                    .allow_pending_two_factor()
+                                    .deny_impersonation(),
+                            )
+                            .route(web::post().to(two_factor::confirm)),
+                    )
+                    .service(
+                        web::resource("/disable")
+                            .wrap(Authorization::require_authenticated().deny_impersonation())
+                            .route(web::post().to(two_factor::disable)),
+                    )
+                    .service(
+                        web::resource("/recovery-codes")
+                            .wrap(Authorization::require_authenticated().deny_impersonation())
+                            .route(web::post().to(two_factor::regenerate_recovery_codes)),
+                    ),
+            )
+            //* Devices and sign-outs are the user's own business, not an impersonating admin's
+            .service(
+                web::resource("/sessions")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::get().to(session::list_sessions))
+                    .route(web::delete().to(session::revoke_other_sessions)),
+            )
+            .service(
+                web::resource("/sessions/{session_id}")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::delete().to(session::revoke_session)),
             ),
     );
-    // .service(
-    //     web::scope("/u")
-    //         .wrap(Authentication)
-    //         .route(
-    //             "/profile/{user_id}",
-    //             web::get().to(super::views::get_profile),
-    //         )
-    //         .route("/profile", web::patch().to(super::views::update_profile)),
-    // );
 }
@@ -0,0 +1,12 @@
+use actix_web::web;
+
+use crate::api::v1::handlers::api_key;
+use crate::domain::middlewares::auth::Authorization;
+
+pub fn register_urls(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/integrations")
+            .wrap(Authorization::require_authenticated().allow_api_key())
+            .route("/key", web::get().to(api_key::current_api_key)),
+    );
+}
@@ -1 +1,5 @@
-pub mod auth;
\ No newline at end of file
+pub mod admin;
+pub mod auth;
+pub mod integration;
+pub mod profile;
+pub mod well_known;
\ No newline at end of file
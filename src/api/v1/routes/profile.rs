@@ -0,0 +1,98 @@
+use actix_web::web;
+
+use crate::api::v1::handlers::{api_key, auth_event, oidc, profile};
+use crate::domain::middlewares::{auth::Authorization, rate_limit::RateLimit};
+
+pub fn register_urls(cfg: &mut web::ServiceConfig) {
+    //* Credentials, linked accounts, sign-in history and account removal stay out of reach of
+    //* impersonating admins
+    cfg.service(
+        web::scope("/u")
+            .service(
+                web::resource("/profile")
+                    .wrap(Authorization::require_authenticated())
+                    .route(web::get().to(profile::get_profile))
+                    .route(web::patch().to(profile::update_profile)),
+            )
+            .service(
+                web::resource("/profile/avatar")
+                    .wrap(Authorization::require_authenticated())
+                    .route(web::post().to(profile::upload_avatar)),
+            )
+            .service(
+                web::resource("/profile/password")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::put().to(profile::change_password))
+                    .route(web::post().to(profile::set_password)),
+            )
+            .service(
+                web::resource("/profile/email")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::post().to(profile::change_email)),
+            )
+            .service(
+                web::resource("/profile/email/verify")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::post().to(profile::confirm_email_change)),
+            )
+            .service(
+                web::resource("/profile/phone/verification")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::post().to(profile::send_phone_verification)),
+            )
+            .service(
+                web::resource("/profile/phone/verify")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(
+                        web::post()
+                            .to(profile::verify_phone)
+                            .wrap(RateLimit::per_ip("otp")),
+                    ),
+            )
+            .service(
+                web::resource("/activity")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::get().to(auth_event::recent_activity)),
+            )
+            .service(
+                web::resource("/identities")
+                    .wrap(Authorization::require_authenticated())
+                    .route(web::get().to(oidc::list_identities)),
+            )
+            .service(
+                web::resource("/identities/{provider}")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::post().to(oidc::link_identity))
+                    .route(web::delete().to(oidc::unlink_identity)),
+            )
+            .service(
+                web::resource("/api-keys")
+                    .wrap(
+                        Authorization::require_authenticated()
+                            .require_verified_email()
+                            .deny_impersonation(),
+                    )
+                    .route(web::get().to(api_key::list_api_keys))
+                    .route(web::post().to(api_key::create_api_key)),
+            )
+            .service(
+                web::resource("/api-keys/{key_id}")
+                    .wrap(
+                        Authorization::require_authenticated()
+                            .require_verified_email()
+                            .deny_impersonation(),
+                    )
+                    .route(web::delete().to(api_key::revoke_api_key)),
+            )
+            .service(
+                web::resource("/account")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::delete().to(profile::delete_account)),
+            )
+            .service(
+                web::resource("/account/export")
+                    .wrap(Authorization::require_authenticated().deny_impersonation())
+                    .route(web::get().to(profile::export_data)),
+            ),
+    );
+}
@@ -0,0 +1,9 @@
+use actix_web::web;
+
+use crate::api::v1::handlers::jwks;
+
+pub fn register_urls(cfg: &mut web::ServiceConfig) {
+    cfg.service(
+        web::scope("/.well-known").route("/jwks.json", web::get().to(jwks::jwks)),
+    );
+}
@@ -1,25 +1,71 @@
+use chrono::{DateTime, Utc};
 use serde::Deserialize;
 use std::time::Duration;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+pub enum JwtAlgorithm {
+    #[default]
+    HS256,
+    EdDSA,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct JwtKeySettings {
+    pub kid: String,
+    pub public_key_path: String,
+    /// Only the key named by `signing_key_id` needs its private half.
+    #[serde(default)]
+    pub private_key_path: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
 pub struct JwtSettings {
+    #[serde(default)]
+    pub algorithm: JwtAlgorithm,
+    #[serde(default)]
     pub secret: String,
-    #[serde(default = "default_expiration")]
-    pub expiration_hours: u64,
+    /// With `EdDSA`, tokens signed with `secret` (no `kid`) keep verifying until this time
+    /// so the switch doesn't sign anyone out; unset, they stop verifying straight away.
+    #[serde(default)]
+    pub legacy_secret_until: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub signing_key_id: Option<String>,
+    #[serde(default)]
+    pub keys: Vec<JwtKeySettings>,
+    #[serde(default = "default_access_token_expiration")]
+    pub access_token_expiration_minutes: u64,
+    #[serde(default = "default_refresh_token_expiration")]
+    pub refresh_token_expiration_days: u32,
     #[serde(default = "default_issuer")]
     pub issuer: String,
+    #[serde(default = "default_audience")]
+    pub audience: String,
+    #[serde(default = "default_leeway")]
+    pub leeway_seconds: u64,
+}
+
+fn default_access_token_expiration() -> u64 {
+    15
 }
 
-fn default_expiration() -> u64 {
-    24
+fn default_refresh_token_expiration() -> u32 {
+    30
 }
 
 fn default_issuer() -> String {
     "karcis-com".to_string()
 }
 
+fn default_audience() -> String {
+    "karcis-com-api".to_string()
+}
+
+fn default_leeway() -> u64 {
+    30
+}
+
 impl JwtSettings {
-    pub fn expiration(&self) -> Duration {
-        Duration::from_secs(self.expiration_hours * 3600)
+    pub fn access_token_expiration(&self) -> Duration {
+        Duration::from_secs(self.access_token_expiration_minutes * 60)
     }
-} 
\ No newline at end of file
+}
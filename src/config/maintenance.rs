@@ -0,0 +1,45 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MaintenanceSettings {
+    /// Runs the purge task in this process; turn off where another instance already does.
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_interval")]
+    pub interval_seconds: u64,
+    /// How long expired or spent rows are kept before being deleted. Keep this above the
+    /// one-hour window the OTP and login link rate limits count over.
+    #[serde(default = "default_retention")]
+    pub retention_hours: i32,
+    /// Audit events older than this keep their outcome but lose the identifier, IP address
+    /// and user agent.
+    #[serde(default = "default_auth_event_retention")]
+    pub auth_event_retention_days: i32,
+}
+
+impl Default for MaintenanceSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+            interval_seconds: default_interval(),
+            retention_hours: default_retention(),
+            auth_event_retention_days: default_auth_event_retention(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_interval() -> u64 {
+    3600
+}
+
+fn default_retention() -> i32 {
+    24
+}
+
+fn default_auth_event_retention() -> i32 {
+    180
+}
@@ -4,19 +4,43 @@ use std::env;
 mod database;
 mod server;
 mod email;
-// mod jwt;
+mod jwt;
+mod maintenance;
+mod oidc;
+mod password;
+mod security;
+mod sms;
+mod upload;
 
 pub use database::DatabaseSettings;
 pub use server::ServerSettings;
 pub use email::EmailSettings;
-// pub use jwt::JwtSettings;
+pub use jwt::{JwtAlgorithm, JwtSettings};
+pub use maintenance::MaintenanceSettings;
+pub use oidc::{OidcProviderSettings, OidcSettings};
+pub use password::{PasswordAlgorithm, PasswordSettings};
+pub use security::SecuritySettings;
+pub use sms::{SmsDriver, SmsSettings};
+pub use upload::UploadSettings;
 
 #[derive(Debug, Deserialize)]
 pub struct Settings {
     pub database: DatabaseSettings,
     pub server: ServerSettings,
     pub email: EmailSettings,
-    // pub jwt: JwtSettings,
+    pub jwt: JwtSettings,
+    #[serde(default)]
+    pub security: SecuritySettings,
+    #[serde(default)]
+    pub password: PasswordSettings,
+    #[serde(default)]
+    pub upload: UploadSettings,
+    #[serde(default)]
+    pub oidc: OidcSettings,
+    #[serde(default)]
+    pub sms: SmsSettings,
+    #[serde(default)]
+    pub maintenance: MaintenanceSettings,
 }
 
 impl Settings {
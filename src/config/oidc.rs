@@ -0,0 +1,49 @@
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcProviderSettings {
+    /// Discovery happens at `{issuer_url}/.well-known/openid-configuration`.
+    pub issuer_url: String,
+    pub client_id: String,
+    /// Left out for public clients, which rely on PKCE alone.
+    #[serde(default)]
+    pub client_secret: Option<String>,
+    pub redirect_uri: String,
+    #[serde(default = "default_scopes")]
+    pub scopes: String,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OidcSettings {
+    /// Keyed by the name used in URLs, e.g. `google`.
+    #[serde(default)]
+    pub providers: HashMap<String, OidcProviderSettings>,
+    /// How long a started sign-in may take before its state expires.
+    #[serde(default = "default_state_ttl")]
+    pub state_ttl_seconds: i32,
+    #[serde(default = "default_http_timeout")]
+    pub http_timeout_seconds: u64,
+}
+
+impl Default for OidcSettings {
+    fn default() -> Self {
+        Self {
+            providers: HashMap::new(),
+            state_ttl_seconds: default_state_ttl(),
+            http_timeout_seconds: default_http_timeout(),
+        }
+    }
+}
+
+fn default_scopes() -> String {
+    "openid email profile".into()
+}
+
+fn default_state_ttl() -> i32 {
+    600
+}
+
+fn default_http_timeout() -> u64 {
+    10
+}
@@ -0,0 +1,93 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PasswordAlgorithm {
+    Bcrypt,
+    #[default]
+    Argon2id,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasswordSettings {
+    #[serde(default = "default_min_length")]
+    pub min_length: usize,
+    /// bcrypt only looks at the first 72 bytes, so longer passwords are rejected.
+    #[serde(default = "default_max_length")]
+    pub max_length: usize,
+    #[serde(default = "default_true")]
+    pub require_lowercase: bool,
+    #[serde(default = "default_true")]
+    pub require_uppercase: bool,
+    #[serde(default = "default_true")]
+    pub require_digit: bool,
+    #[serde(default)]
+    pub require_symbol: bool,
+    /// Reject passwords containing the username or email address.
+    #[serde(default = "default_true")]
+    pub reject_personal_info: bool,
+    /// Reject passwords found in the bundled list of common passwords.
+    #[serde(default = "default_true")]
+    pub reject_common: bool,
+    /// Scheme for new hashes. Existing hashes of any supported scheme keep
+    /// verifying and are upgraded on their user's next login.
+    #[serde(default)]
+    pub algorithm: PasswordAlgorithm,
+    /// Changing the cost rehashes each user's password on their next login.
+    #[serde(default = "default_bcrypt_cost")]
+    pub bcrypt_cost: u32,
+    #[serde(default = "default_argon2_memory")]
+    pub argon2_memory_kib: u32,
+    #[serde(default = "default_argon2_iterations")]
+    pub argon2_iterations: u32,
+    #[serde(default = "default_argon2_parallelism")]
+    pub argon2_parallelism: u32,
+}
+
+impl Default for PasswordSettings {
+    fn default() -> Self {
+        Self {
+            min_length: default_min_length(),
+            max_length: default_max_length(),
+            require_lowercase: true,
+            require_uppercase: true,
+            require_digit: true,
+            require_symbol: false,
+            reject_personal_info: true,
+            reject_common: true,
+            algorithm: PasswordAlgorithm::default(),
+            bcrypt_cost: default_bcrypt_cost(),
+            argon2_memory_kib: default_argon2_memory(),
+            argon2_iterations: default_argon2_iterations(),
+            argon2_parallelism: default_argon2_parallelism(),
+        }
+    }
+}
+
+fn default_min_length() -> usize {
+    8
+}
+
+fn default_max_length() -> usize {
+    72
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_bcrypt_cost() -> u32 {
+    10
+}
+
+fn default_argon2_memory() -> u32 {
+    19456
+}
+
+fn default_argon2_iterations() -> u32 {
+    2
+}
+
+fn default_argon2_parallelism() -> u32 {
+    1
+}
@@ -0,0 +1,171 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SecuritySettings {
+    /// Failed sign-in attempts allowed before the account is locked.
+    #[serde(default = "default_lockout_threshold")]
+    pub lockout_threshold: i32,
+    /// Failures older than this no longer count towards a lockout.
+    #[serde(default = "default_failure_window")]
+    pub failure_window_minutes: i32,
+    /// First lockout duration; it doubles with every lockout in a row.
+    #[serde(default = "default_lockout_base")]
+    pub lockout_base_seconds: u64,
+    #[serde(default = "default_lockout_max")]
+    pub lockout_max_seconds: u64,
+    /// Requests allowed per client IP and endpoint group within `ip_window_seconds`.
+    #[serde(default = "default_ip_max_requests")]
+    pub ip_max_requests: u32,
+    #[serde(default = "default_ip_window")]
+    pub ip_window_seconds: u64,
+    /// Use `X-Forwarded-For`/`Forwarded` for the client IP; only enable behind a trusted proxy.
+    #[serde(default)]
+    pub trust_proxy_headers: bool,
+    /// Minimum gap between two password reset emails to the same account.
+    #[serde(default = "default_forgot_password_cooldown")]
+    pub forgot_password_cooldown_seconds: i32,
+    /// How long a role's permissions are cached before being reloaded.
+    #[serde(default = "default_permission_cache")]
+    pub permission_cache_seconds: u64,
+    /// Lifetime of a token an admin gets to act as another user; it can't be refreshed.
+    #[serde(default = "default_impersonation_minutes")]
+    pub impersonation_minutes: i32,
+    /// Page the login link in magic-link emails points to; the token is appended as `?token=`.
+    #[serde(default = "default_magic_link_url")]
+    pub magic_link_url: String,
+    /// Minimum gap between two login links to the same address.
+    #[serde(default = "default_magic_link_cooldown")]
+    pub magic_link_cooldown_seconds: i32,
+    #[serde(default = "default_magic_link_max_per_hour")]
+    pub magic_link_max_per_hour: i64,
+    /// Email an account when it signs in from a device or IP address it hasn't used before.
+    #[serde(default = "default_login_alerts")]
+    pub login_alerts: bool,
+    /// Page the "this wasn't me" link in login alerts points to; the token is appended as `?token=`.
+    #[serde(default = "default_login_alert_url")]
+    pub login_alert_url: String,
+}
+
+impl Default for SecuritySettings {
+    fn default() -> Self {
+        Self {
+            lockout_threshold: default_lockout_threshold(),
+            failure_window_minutes: default_failure_window(),
+            lockout_base_seconds: default_lockout_base(),
+            lockout_max_seconds: default_lockout_max(),
+            ip_max_requests: default_ip_max_requests(),
+            ip_window_seconds: default_ip_window(),
+            trust_proxy_headers: false,
+            forgot_password_cooldown_seconds: default_forgot_password_cooldown(),
+            permission_cache_seconds: default_permission_cache(),
+            impersonation_minutes: default_impersonation_minutes(),
+            magic_link_url: default_magic_link_url(),
+            magic_link_cooldown_seconds: default_magic_link_cooldown(),
+            magic_link_max_per_hour: default_magic_link_max_per_hour(),
+            login_alerts: default_login_alerts(),
+            login_alert_url: default_login_alert_url(),
+        }
+    }
+}
+
+impl SecuritySettings {
+    /// Lockout duration in seconds for the given number of previous lockouts.
+    pub fn lockout_duration(&self, previous_lockouts: i32) -> u64 {
+        let exponent = previous_lockouts.clamp(0, 20) as u32;
+        self.lockout_base_seconds
+            .saturating_mul(2u64.pow(exponent))
+            .min(self.lockout_max_seconds)
+    }
+}
+
+fn default_lockout_threshold() -> i32 {
+    5
+}
+
+fn default_failure_window() -> i32 {
+    15
+}
+
+fn default_lockout_base() -> u64 {
+    60
+}
+
+fn default_lockout_max() -> u64 {
+    3600
+}
+
+fn default_ip_max_requests() -> u32 {
+    20
+}
+
+fn default_ip_window() -> u64 {
+    60
+}
+
+fn default_forgot_password_cooldown() -> i32 {
+    60
+}
+
+fn default_permission_cache() -> u64 {
+    60
+}
+
+fn default_impersonation_minutes() -> i32 {
+    15
+}
+
+fn default_magic_link_url() -> String {
+    "http://localhost:3000/auth/magic-link".into()
+}
+
+fn default_magic_link_cooldown() -> i32 {
+    60
+}
+
+fn default_magic_link_max_per_hour() -> i64 {
+    5
+}
+
+fn default_login_alerts() -> bool {
+    true
+}
+
+fn default_login_alert_url() -> String {
+    "http://localhost:3000/auth/not-me".into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lockout_duration_doubles_with_each_lockout() {
+        let settings = SecuritySettings::default();
+        assert_eq!(settings.lockout_duration(0), 60);
+        assert_eq!(settings.lockout_duration(1), 120);
+        assert_eq!(settings.lockout_duration(3), 480);
+    }
+
+    #[test]
+    fn lockout_duration_is_capped() {
+        let settings = SecuritySettings::default();
+        assert_eq!(settings.lockout_duration(6), 3600);
+        assert_eq!(settings.lockout_duration(i32::MAX), 3600);
+    }
+
+    #[test]
+    fn lockout_duration_treats_negative_counts_as_none() {
+        let settings = SecuritySettings::default();
+        assert_eq!(settings.lockout_duration(-1), settings.lockout_duration(0));
+    }
+
+    #[test]
+    fn lockout_duration_does_not_overflow_without_a_cap() {
+        let settings = SecuritySettings {
+            lockout_base_seconds: u64::MAX / 2,
+            lockout_max_seconds: u64::MAX,
+            ..Default::default()
+        };
+        assert_eq!(settings.lockout_duration(20), u64::MAX);
+    }
+}
@@ -0,0 +1,40 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SmsDriver {
+    /// Writes messages to the application log.
+    #[default]
+    Log,
+    /// Appends messages to `file_path`, one JSON object per line.
+    File,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SmsSettings {
+    #[serde(default)]
+    pub driver: SmsDriver,
+    #[serde(default = "default_file_path")]
+    pub file_path: String,
+    /// Minimum gap between two verification codes sent to the same account.
+    #[serde(default = "default_resend_cooldown")]
+    pub resend_cooldown_seconds: i32,
+}
+
+impl Default for SmsSettings {
+    fn default() -> Self {
+        Self {
+            driver: SmsDriver::default(),
+            file_path: default_file_path(),
+            resend_cooldown_seconds: default_resend_cooldown(),
+        }
+    }
+}
+
+fn default_file_path() -> String {
+    "storage/sms.log".into()
+}
+
+fn default_resend_cooldown() -> i32 {
+    60
+}
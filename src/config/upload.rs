@@ -0,0 +1,58 @@
+use serde::Deserialize;
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct UploadSettings {
+    /// Directory uploaded files are written to.
+    #[serde(default = "default_dir")]
+    pub dir: String,
+    /// URL path the upload directory is served under.
+    #[serde(default = "default_public_path")]
+    pub public_path: String,
+    #[serde(default = "default_avatar_max_bytes")]
+    pub avatar_max_bytes: usize,
+    /// Edge length of the stored square avatar, in pixels.
+    #[serde(default = "default_avatar_size")]
+    pub avatar_size: u32,
+    #[serde(default = "default_thumbnail_size")]
+    pub thumbnail_size: u32,
+    /// Larger images are refused before being decoded.
+    #[serde(default = "default_max_dimension")]
+    pub max_dimension: u32,
+}
+
+impl Default for UploadSettings {
+    fn default() -> Self {
+        Self {
+            dir: default_dir(),
+            public_path: default_public_path(),
+            avatar_max_bytes: default_avatar_max_bytes(),
+            avatar_size: default_avatar_size(),
+            thumbnail_size: default_thumbnail_size(),
+            max_dimension: default_max_dimension(),
+        }
+    }
+}
+
+fn default_dir() -> String {
+    "uploads".into()
+}
+
+fn default_public_path() -> String {
+    "/uploads".into()
+}
+
+fn default_avatar_max_bytes() -> usize {
+    5 * 1024 * 1024
+}
+
+fn default_avatar_size() -> u32 {
+    512
+}
+
+fn default_thumbnail_size() -> u32 {
+    128
+}
+
+fn default_max_dimension() -> u32 {
+    8000
+}
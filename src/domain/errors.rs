@@ -1,4 +1,4 @@
-use actix_web::{HttpResponse, ResponseError};
+use actix_web::{http::header::RETRY_AFTER, HttpResponse, ResponseError};
 use derive_more::Display;
 use serde::Serialize;
 
@@ -7,29 +7,32 @@ use crate::domain::models::StandardResponse;
 #[derive(Debug, Display, Serialize)]
 pub enum AppError {
     // Validation errors
-    #[display(fmt = "Validation failed")]
+    #[display("Validation failed")]
     ValidationError(String),
     
     // Authentication errors
-    #[display(fmt = "Invalid credentials")]
+    #[display("Invalid credentials")]
     InvalidCredentials,
-    #[display(fmt = "Unauthorized")]
+    #[display("Unauthorized")]
     Unauthorized,
-    #[display(fmt = "Forbidden")]
+    #[display("Forbidden")]
     Forbidden,
+    /// Seconds until the client may retry
+    #[display("Too many requests")]
+    TooManyRequests(u64),
     
     // Resource errors
-    #[display(fmt = "Resource not found")]
+    #[display("Resource not found")]
     NotFound(String),
-    #[display(fmt = "Resource already exists")]
+    #[display("Resource already exists")]
     Conflict(String),
     
     // Database errors
-    #[display(fmt = "Database error")]
+    #[display("Database error")]
     DatabaseError(String),
     
     // Internal errors
-    #[display(fmt = "Internal server error")]
+    #[display("Internal server error")]
     InternalServerError,
 }
 
@@ -60,6 +63,14 @@ impl ResponseError for AppError {
                     Some("FORBIDDEN".to_string())
                 ))
             },
+            AppError::TooManyRequests(retry_after) => {
+                HttpResponse::TooManyRequests()
+                    .insert_header((RETRY_AFTER, retry_after.to_string()))
+                    .json(StandardResponse::<()>::error(
+                        format!("Too many attempts. Try again in {} seconds.", retry_after),
+                        Some("TOO_MANY_REQUESTS".to_string())
+                    ))
+            },
             AppError::NotFound(resource) => {
                 HttpResponse::NotFound().json(StandardResponse::<()>::error(
                     format!("{} not found", resource),
@@ -86,4 +97,13 @@ impl ResponseError for AppError {
             },
         }
     }
-}
\ No newline at end of file
+}
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        log::error!("Database error: {:?}", error);
+        match error {
+            sqlx::Error::RowNotFound => AppError::NotFound("Resource".to_string()),
+            _ => AppError::DatabaseError(error.to_string()),
+        }
+    }
+}
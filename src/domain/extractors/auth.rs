@@ -0,0 +1,211 @@
+use std::{
+    future::{ready, Ready},
+    marker::PhantomData,
+    ops::Deref,
+};
+
+use actix_web::{dev::Payload, FromRequest, HttpMessage, HttpRequest};
+
+use crate::domain::{
+    errors::AppError,
+    models::{api_key::ApiKey, token::Claims, user::User},
+};
+
+/// The authenticated user, put in request extensions by the `Authorization` middleware.
+#[derive(Debug, Clone)]
+pub struct AuthUser(pub User);
+
+/// Claims of the verified access token.
+#[derive(Debug, Clone)]
+pub struct AuthClaims(pub Claims);
+
+/// The raw bearer token the request was authenticated with.
+#[derive(Debug, Clone)]
+pub struct BearerToken(pub String);
+
+/// The API key the request was authenticated with, on routes that accept `X-Api-Key`.
+/// Handlers that take a hotel from the request body must check it with `allows_hotel`.
+#[derive(Debug, Clone)]
+pub struct AuthApiKey(pub ApiKey);
+
+impl Deref for AuthUser {
+    type Target = User;
+
+    fn deref(&self) -> &User {
+        &self.0
+    }
+}
+
+impl Deref for AuthClaims {
+    type Target = Claims;
+
+    fn deref(&self) -> &Claims {
+        &self.0
+    }
+}
+
+impl Deref for BearerToken {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Deref for AuthApiKey {
+    type Target = ApiKey;
+
+    fn deref(&self) -> &ApiKey {
+        &self.0
+    }
+}
+
+/// Clones a value the middleware stored, answering 401 when the route isn't behind it.
+fn from_extensions<T: Clone + 'static>(req: &HttpRequest) -> Ready<Result<T, AppError>> {
+    ready(req.extensions().get::<T>().cloned().ok_or(AppError::Unauthorized))
+}
+
+impl FromRequest for AuthUser {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        from_extensions(req)
+    }
+}
+
+impl FromRequest for AuthClaims {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        from_extensions(req)
+    }
+}
+
+impl FromRequest for BearerToken {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        from_extensions(req)
+    }
+}
+
+impl FromRequest for AuthApiKey {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        from_extensions(req)
+    }
+}
+
+/// Marker for a built-in role, used with `RequireRole`.
+pub trait RoleMarker {
+    const ROLE_ID: i32;
+}
+
+pub struct Tenant;
+
+impl RoleMarker for Tenant {
+    const ROLE_ID: i32 = 3;
+}
+
+/// The authenticated user, provided their role is `R`; answers 403 otherwise.
+pub struct RequireRole<R: RoleMarker> {
+    pub user: User,
+    role: PhantomData<R>,
+}
+
+impl<R: RoleMarker> Deref for RequireRole<R> {
+    type Target = User;
+
+    fn deref(&self) -> &User {
+        &self.user
+    }
+}
+
+impl<R: RoleMarker> FromRequest for RequireRole<R> {
+    type Error = AppError;
+    type Future = Ready<Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let result = match req.extensions().get::<AuthUser>() {
+            Some(AuthUser(user)) if user.role_id == R::ROLE_ID => Ok(RequireRole {
+                user: user.clone(),
+                role: PhantomData,
+            }),
+            Some(_) => Err(AppError::Forbidden),
+            None => Err(AppError::Unauthorized),
+        };
+        ready(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+
+    use super::*;
+
+    fn user(role_id: i32) -> User {
+        User {
+            id: 42,
+            first_name: "Budi".into(),
+            last_name: "Santoso".into(),
+            username: "budi".into(),
+            email: "budi@example.com".into(),
+            password_hash: String::new(),
+            title: "Mr".into(),
+            image: None,
+            phone: "+6281234567890".into(),
+            role_id,
+            email_verified_at: None,
+            deleted_at: None,
+            phone_verified_at: None,
+        }
+    }
+
+    fn authenticated(role_id: i32) -> HttpRequest {
+        let req = TestRequest::default().to_http_request();
+        req.extensions_mut().insert(AuthUser(user(role_id)));
+        req.extensions_mut().insert(BearerToken("token".into()));
+        req
+    }
+
+    #[actix_web::test]
+    async fn extracts_what_the_middleware_stored() {
+        let req = authenticated(2);
+        assert_eq!(AuthUser::extract(&req).await.unwrap().id, 42);
+        assert_eq!(&*BearerToken::extract(&req).await.unwrap(), "token");
+    }
+
+    #[actix_web::test]
+    async fn answers_unauthorized_outside_the_middleware() {
+        let req = TestRequest::default().to_http_request();
+        assert!(matches!(AuthUser::extract(&req).await, Err(AppError::Unauthorized)));
+        assert!(matches!(AuthClaims::extract(&req).await, Err(AppError::Unauthorized)));
+        assert!(matches!(BearerToken::extract(&req).await, Err(AppError::Unauthorized)));
+        assert!(matches!(AuthApiKey::extract(&req).await, Err(AppError::Unauthorized)));
+    }
+
+    #[actix_web::test]
+    async fn does_not_mistake_another_string_for_the_token() {
+        let req = TestRequest::default().to_http_request();
+        req.extensions_mut().insert(String::from("not a token"));
+        assert!(matches!(BearerToken::extract(&req).await, Err(AppError::Unauthorized)));
+    }
+
+    #[actix_web::test]
+    async fn require_role_checks_the_role() {
+        let tenant = RequireRole::<Tenant>::extract(&authenticated(3)).await.unwrap();
+        assert_eq!(tenant.id, 42);
+        assert!(matches!(
+            RequireRole::<Tenant>::extract(&authenticated(2)).await,
+            Err(AppError::Forbidden)
+        ));
+        let req = TestRequest::default().to_http_request();
+        assert!(matches!(RequireRole::<Tenant>::extract(&req).await, Err(AppError::Unauthorized)));
+    }
+}
@@ -0,0 +1 @@
+pub mod auth;
\ No newline at end of file
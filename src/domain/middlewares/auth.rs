@@ -1,6 +1,8 @@
 use std::{
+    collections::{HashMap, HashSet},
     future::{ready, Ready},
     rc::Rc,
+    sync::Arc,
 };
 
 use actix_web::{
@@ -11,35 +13,42 @@ use actix_web::{
     Error, HttpMessage, HttpResponse,
 };
 use futures_util::{future::LocalBoxFuture, FutureExt};
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
-use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::models::user::User, domain::services::TokenService,
+    domain::extractors::auth::{AuthApiKey, AuthClaims, AuthUser, BearerToken},
+    domain::models::{session::SessionMetadata, user::User},
+    domain::services::{
+        api_key::create_api_key_service, permission::create_permission_service,
+        two_factor::create_two_factor_service, ImpersonationService, SessionService,
+    },
     infrastructure::database::PostgresPool,
+    shared::utils::{permission_cache::PermissionCache, token_signing::TokenCodec},
 };
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct Claims {
-    pub id: i64,
-    pub first_name: String,
-    pub last_name: String,
-    pub email: String,
-    pub role_id: i32,
-    pub exp: usize,
-}
-
 #[derive(Debug, Clone)]
 pub struct AuthorizationConfig {
     pub required_roles: Vec<i32>,
     pub check_permissions: bool,
+    pub required_permissions: Vec<String>,
+    pub require_verified_email: bool,
+    pub allow_pending_two_factor: bool,
+    pub allow_api_key: bool,
+    pub deny_impersonation: bool,
 }
 
+/// Header integrations send their API key in, instead of `Authorization: Bearer`.
+pub const API_KEY_HEADER: &str = "X-Api-Key";
+
 impl Default for AuthorizationConfig {
     fn default() -> Self {
         Self {
             required_roles: vec![],
             check_permissions: false,
+            required_permissions: vec![],
+            require_verified_email: false,
+            allow_pending_two_factor: false,
+            allow_api_key: false,
+            deny_impersonation: false,
         }
     }
 }
@@ -57,18 +66,57 @@ impl Authorization {
         Self {
             config: AuthorizationConfig {
                 required_roles: roles,
-                check_permissions: false,
+                ..Default::default()
             },
         }
     }
 
-    pub fn require_admin() -> Self {
-        Self::require_roles(vec![1]) // Assuming role_id 1 is admin
-    }
-
     pub fn require_user() -> Self {
         Self::require_roles(vec![2]) // Assuming role_id 2 is regular user
     }
+
+    pub fn require_authenticated() -> Self {
+        Self::new(AuthorizationConfig::default())
+    }
+
+    /// Requires the user's role to be granted the permission, e.g. `"hotel:write"`.
+    pub fn require_permission(permission: &str) -> Self {
+        Self::new(AuthorizationConfig {
+            check_permissions: true,
+            required_permissions: vec![permission.to_string()],
+            ..Default::default()
+        })
+    }
+
+    /// Rejects users who haven't confirmed their email address yet, e.g. on
+    /// API key, booking or wallet top-up routes.
+    pub fn require_verified_email(mut self) -> Self {
+        self.config.require_verified_email = true;
+        self
+    }
+
+    /// Lets through users whose role requires 2FA but who haven't enrolled yet,
+    /// so they can still reach the enrolment routes.
+    pub fn allow_pending_two_factor(mut self) -> Self {
+        self.config.allow_pending_two_factor = true;
+        self
+    }
+
+    /// Also accepts an `X-Api-Key` header. Required permissions must then be
+    /// granted both to the owner's role and to the key's scopes; without any, the key
+    /// needs at least one scope the role still grants. A `{hotel_id}` path segment or
+    /// `hotel_id` query parameter must be one of the owner's hotels the key covers.
+    pub fn allow_api_key(mut self) -> Self {
+        self.config.allow_api_key = true;
+        self
+    }
+
+    /// Refuses tokens an admin got by impersonating the user, e.g. on wallet,
+    /// payment and credential routes.
+    pub fn deny_impersonation(mut self) -> Self {
+        self.config.deny_impersonation = true;
+        self
+    }
 }
 
 impl<S, B> Transform<S, ServiceRequest> for Authorization
@@ -111,6 +159,17 @@ where
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let auth_header = req.headers().get(AUTHORIZATION);
         if auth_header.is_none() {
+            if let Some(api_key) = req.headers().get(API_KEY_HEADER) {
+                if !self.config.allow_api_key {
+                    let http_res = HttpResponse::Unauthorized().json(serde_json::json!({
+                        "status": "error",
+                        "message": "API keys are not accepted on this route"
+                    }));
+                    return (async move { Ok(reject(req, http_res)) }).boxed_local();
+                }
+                let api_key = api_key.to_str().unwrap_or_default().to_string();
+                return self.call_with_api_key(req, api_key);
+            }
             let http_res = HttpResponse::Unauthorized().json(serde_json::json!({
                 "status": "error",
                 "message": "Authorization header is required"
@@ -139,10 +198,17 @@ where
             // Get database pool
             let pool = req.app_data::<web::Data<PostgresPool>>().unwrap().get_ref();
 
-            // Check if token is revoked
-            if TokenService::is_token_revoked(pool, &token)
+            // Verify and decode JWT token
+            let codec = req.app_data::<web::Data<TokenCodec>>().unwrap().get_ref();
+
+            let claims = codec
+                .verify(&token)
+                .map_err(|e| actix_web::error::ErrorUnauthorized(format!("Invalid token: {}", e)))?;
+
+            // Check that the session behind the token is still active
+            if !SessionService::touch(pool, claims.jti, claims.id)
                 .await
-                .map_err(|e| actix_web::error::ErrorInternalServerError(e))?
+                .map_err(actix_web::error::ErrorInternalServerError)?
             {
                 let http_res = HttpResponse::Unauthorized().json(serde_json::json!({
                     "status": "error",
@@ -153,19 +219,6 @@ where
                 return Ok(res.map_into_right_body());
             }
 
-            // Verify and decode JWT token
-            let secret = std::env::var("APP_KEY")
-                .map_err(|_| actix_web::error::ErrorInternalServerError("APP_KEY not found"))?;
-
-            let token_data = decode::<Claims>(
-                &token,
-                &DecodingKey::from_secret(secret.as_ref()),
-                &Validation::new(Algorithm::HS256),
-            )
-            .map_err(|e| actix_web::error::ErrorUnauthorized(format!("Invalid token: {}", e)))?;
-
-            let claims = token_data.claims;
-
             // Check if user exists and is not deleted
             let user = sqlx::query_as::<_, User>(
                 "SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL",
@@ -198,10 +251,84 @@ where
                 let res = ServiceResponse::new(http_req, http_res);
                 return Ok(res.map_into_right_body());
             }
+
+            // Check permission-based authorization
+            if config.check_permissions {
+                let permissions = role_permissions(&req, pool, user.role_id).await?;
+                let missing: Vec<&String> = config
+                    .required_permissions
+                    .iter()
+                    .filter(|permission| !permissions.contains(*permission))
+                    .collect();
+                if !missing.is_empty() {
+                    let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                        "status": "error",
+                        "message": format!("Insufficient permissions. Missing: {:?}", missing)
+                    }));
+                    let (http_req, _) = req.into_parts();
+                    let res = ServiceResponse::new(http_req, http_res);
+                    return Ok(res.map_into_right_body());
+                }
+            }
+
+            if config.require_verified_email && user.email_verified_at.is_none() {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": "Email address has not been verified"
+                }));
+                let (http_req, _) = req.into_parts();
+                let res = ServiceResponse::new(http_req, http_res);
+                return Ok(res.map_into_right_body());
+            }
+
+            // Check that users whose role requires 2FA have enrolled
+            if !config.allow_pending_two_factor
+                && create_two_factor_service(pool.clone())
+                    .enrollment_required(&user)
+                    .await
+                    .map_err(actix_web::error::ErrorInternalServerError)?
+            {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": "Two-factor authentication must be enabled for this account"
+                }));
+                let (http_req, _) = req.into_parts();
+                let res = ServiceResponse::new(http_req, http_res);
+                return Ok(res.map_into_right_body());
+            }
+
+            // Requests made while impersonating are audited, and refused where the route opts out
+            if let Some(actor) = &claims.act {
+                if config.deny_impersonation {
+                    let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                        "status": "error",
+                        "message": "This action is not available while impersonating a user"
+                    }));
+                    return Ok(reject(req, http_res));
+                }
+                let ip_address = SessionMetadata::from_request(req.request()).ip_address;
+                log::info!(
+                    "Impersonated request: user {} acting as user {}: {} {}",
+                    actor.sub,
+                    claims.id,
+                    req.method(),
+                    req.path()
+                );
+                ImpersonationService::record_request(
+                    pool,
+                    claims.jti,
+                    req.method().as_str(),
+                    req.path(),
+                    ip_address.as_deref(),
+                )
+                .await
+                .map_err(actix_web::error::ErrorInternalServerError)?;
+            }
+
             // Add user information to request extensions for use in handlers
-            req.extensions_mut().insert(user);
-            req.extensions_mut().insert(claims);
-            req.extensions_mut().insert(token);
+            req.extensions_mut().insert(AuthUser(user));
+            req.extensions_mut().insert(AuthClaims(claims));
+            req.extensions_mut().insert(BearerToken(token));
 
             let res = service.call(req).await?;
             Ok(res.map_into_left_body())
@@ -209,11 +336,132 @@ where
     }
 }
 
-// Helper functions for common authorization patterns
-pub fn require_admin() -> Authorization {
-    Authorization::require_admin()
+impl<S, B> AuthorizationMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    fn call_with_api_key(
+        &self,
+        req: ServiceRequest,
+        api_key: String,
+    ) -> LocalBoxFuture<'static, Result<ServiceResponse<EitherBody<B>>, Error>> {
+        let config = self.config.clone();
+        let service = Rc::clone(&self.service);
+
+        Box::pin(async move {
+            let pool = req.app_data::<web::Data<PostgresPool>>().unwrap().get_ref().clone();
+            let ip_address = SessionMetadata::from_request(req.request()).ip_address;
+
+            let authenticated = create_api_key_service(pool.clone())
+                .authenticate(&api_key, ip_address.as_deref())
+                .await
+                .map_err(actix_web::error::ErrorInternalServerError)?;
+            let (api_key, user) = match authenticated {
+                Some(authenticated) => authenticated,
+                None => {
+                    let http_res = HttpResponse::Unauthorized().json(serde_json::json!({
+                        "status": "error",
+                        "message": "Invalid, expired or revoked API key"
+                    }));
+                    return Ok(reject(req, http_res));
+                }
+            };
+
+            if !config.required_roles.is_empty() && !config.required_roles.contains(&user.role_id) {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": format!("Insufficient permissions. Required roles: {:?}, User role: {}", config.required_roles, user.role_id)
+                }));
+                return Ok(reject(req, http_res));
+            }
+
+            // The key only carries the scopes its owner's role still has
+            let permissions = role_permissions(&req, &pool, user.role_id).await?;
+            if config.check_permissions {
+                let missing: Vec<&String> = config
+                    .required_permissions
+                    .iter()
+                    .filter(|permission| !permissions.contains(*permission) || !api_key.has_scope(permission))
+                    .collect();
+                if !missing.is_empty() {
+                    let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                        "status": "error",
+                        "message": format!("Insufficient permissions. Missing: {:?}", missing)
+                    }));
+                    return Ok(reject(req, http_res));
+                }
+            } else if !api_key.scopes.iter().any(|scope| permissions.contains(scope)) {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": "API key has no scopes left"
+                }));
+                return Ok(reject(req, http_res));
+            }
+
+            // A hotel named in the path or query string must be one the key reaches; the path segment is
+            // only matched when this wraps the resource rather than an enclosing scope
+            let mut query = web::Query::<HashMap<String, String>>::from_query(req.query_string())
+                .map(|query| query.into_inner())
+                .unwrap_or_default();
+            let hotel_ids = [req.match_info().get("hotel_id").map(String::from), query.remove("hotel_id")];
+            if !hotel_ids
+                .iter()
+                .flatten()
+                .all(|hotel_id| hotel_id.parse::<i64>().is_ok_and(|hotel_id| api_key.allows_hotel(hotel_id)))
+            {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": "API key is not allowed to access this hotel"
+                }));
+                return Ok(reject(req, http_res));
+            }
+
+            if config.require_verified_email && user.email_verified_at.is_none() {
+                let http_res = HttpResponse::Forbidden().json(serde_json::json!({
+                    "status": "error",
+                    "message": "Email address has not been verified"
+                }));
+                return Ok(reject(req, http_res));
+            }
+
+            // No two-factor check: keys are only created from a session that passed it
+            req.extensions_mut().insert(AuthUser(user));
+            req.extensions_mut().insert(AuthApiKey(api_key));
+
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
+        })
+    }
 }
 
+/// Permission names granted to the role, from the shared cache when it is registered.
+async fn role_permissions(
+    req: &ServiceRequest,
+    pool: &PostgresPool,
+    role_id: i32,
+) -> Result<Arc<HashSet<String>>, Error> {
+    let cache = req.app_data::<web::Data<PermissionCache>>();
+    if let Some(permissions) = cache.and_then(|cache| cache.get(role_id)) {
+        return Ok(permissions);
+    }
+    let loaded = create_permission_service(pool.clone())
+        .for_role(role_id)
+        .await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+    Ok(match cache {
+        Some(cache) => cache.insert(role_id, loaded),
+        None => Arc::new(loaded.into_iter().collect()),
+    })
+}
+
+fn reject<B>(req: ServiceRequest, http_res: HttpResponse) -> ServiceResponse<EitherBody<B>> {
+    let (http_req, _) = req.into_parts();
+    ServiceResponse::new(http_req, http_res).map_into_right_body()
+}
+
+// Helper functions for common authorization patterns
 pub fn require_user() -> Authorization {
     Authorization::require_user()
 }
@@ -225,21 +473,6 @@ pub fn require_roles(roles: Vec<i32>) -> Authorization {
 pub fn require_any_role(roles: Vec<i32>) -> Authorization {
     Authorization::new(AuthorizationConfig {
         required_roles: roles,
-        check_permissions: false,
+        ..Default::default()
     })
 }
-
-// Extract user from request extensions
-pub fn get_current_user(req: &ServiceRequest) -> Option<User> {
-    req.extensions().get::<User>().cloned()
-}
-
-// Extract claims from request extensions
-pub fn get_current_claims(req: &ServiceRequest) -> Option<Claims> {
-    req.extensions().get::<Claims>().cloned()
-}
-
-// Extract raw token from request extensions
-pub fn get_current_token(req: &ServiceRequest) -> Option<String> {
-    req.extensions().get::<String>().cloned()
-}
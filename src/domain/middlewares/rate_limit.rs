@@ -0,0 +1,103 @@
+use std::{
+    future::{ready, Ready},
+    rc::Rc,
+};
+
+use actix_web::{
+    body::EitherBody,
+    dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform},
+    web, Error, HttpRequest, ResponseError,
+};
+use futures_util::future::LocalBoxFuture;
+
+use crate::{
+    config::SecuritySettings, domain::errors::AppError,
+    shared::utils::rate_limiter::RateLimiter,
+};
+
+/// Throttles requests per client IP. Routes sharing a bucket share the same budget.
+pub struct RateLimit {
+    bucket: &'static str,
+}
+
+impl RateLimit {
+    pub fn per_ip(bucket: &'static str) -> Self {
+        Self { bucket }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RateLimit
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = RateLimitMiddleware<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RateLimitMiddleware {
+            service: Rc::new(service),
+            bucket: self.bucket,
+        }))
+    }
+}
+
+pub struct RateLimitMiddleware<S> {
+    service: Rc<S>,
+    bucket: &'static str,
+}
+
+/// Client IP of a request; forwarding headers only count behind a trusted proxy.
+pub fn request_ip(req: &HttpRequest, trust_proxy_headers: bool) -> Option<String> {
+    if trust_proxy_headers {
+        req.connection_info().realip_remote_addr().map(str::to_string)
+    } else {
+        req.peer_addr().map(|addr| addr.ip().to_string())
+    }
+}
+
+/// Client IP used for throttling.
+pub fn client_ip(req: &ServiceRequest, trust_proxy_headers: bool) -> String {
+    request_ip(req.request(), trust_proxy_headers).unwrap_or_else(|| "unknown".to_string())
+}
+
+impl<S, B> Service<ServiceRequest> for RateLimitMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: 'static,
+{
+    type Response = ServiceResponse<EitherBody<B>>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let limiter = req.app_data::<web::Data<RateLimiter>>().cloned();
+        let trust_proxy_headers = req
+            .app_data::<web::Data<SecuritySettings>>()
+            .is_some_and(|settings| settings.trust_proxy_headers);
+
+        if let Some(limiter) = limiter {
+            let key = format!("{}:{}", self.bucket, client_ip(&req, trust_proxy_headers));
+            if let Err(retry_after) = limiter.check(&key) {
+                log::warn!("Rate limit exceeded for {}", key);
+                let http_res = AppError::TooManyRequests(retry_after).error_response();
+                let (http_req, _) = req.into_parts();
+                let res = ServiceResponse::new(http_req, http_res);
+                return Box::pin(async move { Ok(res.map_into_right_body()) });
+            }
+        }
+
+        let service = Rc::clone(&self.service);
+        Box::pin(async move {
+            let res = service.call(req).await?;
+            Ok(res.map_into_left_body())
+        })
+    }
+}
@@ -1,5 +1,6 @@
 // Domain models and business logic
-// pub mod errors;
+pub mod errors;
+pub mod extractors;
 pub mod middlewares;
 pub mod models;
 pub mod services;
@@ -0,0 +1,41 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct ApiKey {
+    pub id: i64,
+    pub user_id: i64,
+    pub name: String,
+    pub prefix: String,
+    #[serde(skip_serializing)]
+    pub key_hash: String,
+    pub scopes: Vec<String>,
+    pub hotel_ids: Vec<i64>,
+    pub last_used_at: Option<NaiveDateTime>,
+    pub last_used_ip: Option<String>,
+    pub expired_at: Option<NaiveDateTime>,
+    pub revoked_at: Option<NaiveDateTime>,
+    pub created_at: NaiveDateTime,
+}
+
+impl ApiKey {
+    pub fn has_scope(&self, scope: &str) -> bool {
+        self.scopes.iter().any(|granted| granted == scope)
+    }
+
+    /// Whether the key may act on the hotel. Stored keys without a hotel list cover all of the
+    /// owner's hotels; an authenticated key has `hotel_ids` resolved to exactly those it reaches.
+    pub fn allows_hotel(&self, hotel_id: i64) -> bool {
+        self.hotel_ids.contains(&hotel_id)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateApiKeyPayload {
+    pub name: String,
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub hotel_ids: Vec<i64>,
+    pub expires_in_days: Option<i32>,
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use crate::domain::models::{token::AuthTokens, two_factor::TwoFactorChallenge, user::User};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RegisterPayload {
     pub first_name: String,
@@ -9,16 +11,73 @@ pub struct RegisterPayload {
     pub password: String,
     pub phone: String,
     pub title: String,
-    pub image: String,
+    /// Optional; an avatar can be uploaded after signing up
+    #[serde(default)]
+    pub image: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LoginPayload {
-    pub username: String,
+    /// Username or email address
+    #[serde(alias = "username", alias = "email")]
+    pub identifier: String,
     pub password: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ForgotPasswordPayload {
     pub email: String,
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OtpCheckPayload {
+    pub email: String,
+    pub otp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetPasswordPayload {
+    pub email: String,
+    pub otp: String,
+    pub password: String,
+    pub password_confirmation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenPayload {
+    pub refresh_token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEmailPayload {
+    pub email: String,
+    pub otp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResendVerificationPayload {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkPayload {
+    pub email: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MagicLinkLoginPayload {
+    pub token: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginAlertPayload {
+    pub token: String,
+}
+
+
+/// Result of checking a username and password.
+pub enum LoginOutcome {
+    Authenticated(Box<User>, AuthTokens),
+    //* The account has 2FA enabled; no session exists until the challenge is answered
+    TwoFactorRequired(TwoFactorChallenge),
+}
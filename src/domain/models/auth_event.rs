@@ -0,0 +1,121 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::domain::models::session::SessionMetadata;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AuthEvent {
+    pub id: i64,
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub event: String,
+    pub outcome: String,
+    pub identifier: Option<String>,
+    pub detail: Option<String>,
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub anonymized_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuthEventKind {
+    Login,
+    Logout,
+    PasswordReset,
+    PasswordChange,
+    RoleChange,
+    TokenRevocation,
+}
+
+impl AuthEventKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuthEventKind::Login => "login",
+            AuthEventKind::Logout => "logout",
+            AuthEventKind::PasswordReset => "password_reset",
+            AuthEventKind::PasswordChange => "password_change",
+            AuthEventKind::RoleChange => "role_change",
+            AuthEventKind::TokenRevocation => "token_revocation",
+        }
+    }
+}
+
+/// An event about to be written, built up from the request that caused it.
+#[derive(Debug, Clone)]
+pub struct NewAuthEvent {
+    pub kind: AuthEventKind,
+    pub success: bool,
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    /// What the client signed in as; only stored when it names no account
+    pub identifier: Option<String>,
+    pub detail: Option<String>,
+    pub metadata: SessionMetadata,
+}
+
+impl NewAuthEvent {
+    pub fn success(kind: AuthEventKind, metadata: SessionMetadata) -> Self {
+        Self::new(kind, true, metadata)
+    }
+
+    pub fn failure(kind: AuthEventKind, metadata: SessionMetadata) -> Self {
+        Self::new(kind, false, metadata)
+    }
+
+    fn new(kind: AuthEventKind, success: bool, metadata: SessionMetadata) -> Self {
+        Self {
+            kind,
+            success,
+            user_id: None,
+            actor_id: None,
+            identifier: None,
+            detail: None,
+            metadata,
+        }
+    }
+
+    pub fn user(mut self, user_id: i64) -> Self {
+        self.user_id = Some(user_id);
+        self
+    }
+
+    pub fn actor(mut self, actor_id: Option<i64>) -> Self {
+        self.actor_id = actor_id;
+        self
+    }
+
+    pub fn identifier(mut self, identifier: &str) -> Self {
+        self.identifier = Some(identifier.trim().chars().take(255).collect());
+        self
+    }
+
+    pub fn detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    pub fn outcome(&self) -> &'static str {
+        if self.success {
+            "success"
+        } else {
+            "failure"
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuthEventQuery {
+    pub user_id: Option<i64>,
+    pub actor_id: Option<i64>,
+    pub event: Option<String>,
+    pub outcome: Option<String>,
+    pub ip_address: Option<String>,
+    /// Inclusive lower bound on `created_at`
+    pub from: Option<NaiveDateTime>,
+    /// Exclusive upper bound on `created_at`
+    pub to: Option<NaiveDateTime>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
@@ -0,0 +1,37 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Impersonation {
+    pub session_id: Uuid,
+    pub actor_id: i64,
+    pub user_id: i64,
+    pub reason: String,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub expired_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ImpersonationRequest {
+    pub id: i64,
+    pub session_id: Uuid,
+    pub method: String,
+    pub path: String,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonatePayload {
+    pub reason: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImpersonationQuery {
+    pub actor_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,31 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct AccountLockout {
+    pub user_id: i64,
+    pub failed_attempts: i32,
+    pub lockout_count: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    pub last_failed_at: Option<NaiveDateTime>,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LockoutEvent {
+    pub id: i64,
+    pub user_id: i64,
+    pub event: String,
+    pub failed_attempts: i32,
+    pub locked_until: Option<NaiveDateTime>,
+    pub ip_address: Option<String>,
+    pub actor_id: Option<i64>,
+    pub created_at: NaiveDateTime,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LockoutEventQuery {
+    pub user_id: Option<i64>,
+    pub limit: Option<i64>,
+}
@@ -0,0 +1,17 @@
+use serde::Serialize;
+
+/// Rows removed, or for the audit trail anonymised, by one run of the purge task.
+#[derive(Debug, Serialize)]
+pub struct PurgeReport {
+    pub sessions: u64,
+    pub refresh_tokens: u64,
+    pub otp_codes: u64,
+    pub oidc_login_states: u64,
+    pub auth_events: u64,
+}
+
+impl PurgeReport {
+    pub fn total(&self) -> u64 {
+        self.sessions + self.refresh_tokens + self.otp_codes + self.oidc_login_states + self.auth_events
+    }
+}
@@ -1,6 +1,17 @@
+pub mod api_key;
 pub mod auth;
+pub mod auth_event;
+pub mod impersonation;
+pub mod lockout;
+pub mod maintenance;
+pub mod oidc;
 pub mod otp;
+pub mod permission;
+pub mod profile;
+pub mod role;
+pub mod session;
 pub mod token;
+pub mod two_factor;
 pub mod user;
 
 use chrono::{DateTime, Utc};
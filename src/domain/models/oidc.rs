@@ -0,0 +1,39 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+use crate::domain::models::auth::LoginOutcome;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct UserIdentity {
+    pub id: i64,
+    pub user_id: i64,
+    pub provider: String,
+    pub subject: String,
+    pub email: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_login_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, FromRow)]
+pub struct OidcLoginState {
+    pub provider: String,
+    pub nonce: String,
+    pub code_verifier: String,
+    pub link_user_id: Option<i64>,
+}
+
+/// Query string the provider redirects back with.
+#[derive(Debug, Deserialize)]
+pub struct OidcCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// What a completed provider round trip did.
+pub enum OidcOutcome {
+    Login(LoginOutcome),
+    Linked(UserIdentity),
+}
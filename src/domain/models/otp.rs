@@ -1,12 +1,54 @@
-use chrono::{DateTime, Utc};
+use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
 pub struct Otp {
     pub id: i64,
-    pub otp: String,
+    pub user_id: Option<i64>,
+    pub code: Option<String>,
+    pub purpose: String,
+    pub attempts: i32,
     pub is_active: bool,
-    pub created_at: DateTime<Utc>,
-    pub updated_at: DateTime<Utc>,
-}
\ No newline at end of file
+    pub created_at: NaiveDateTime,
+    pub expired_at: NaiveDateTime,
+    pub used_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OtpPurpose {
+    PasswordReset,
+    EmailVerification,
+    TwoFactorLogin,
+    EmailChange,
+    PhoneVerification,
+    MagicLink,
+    LoginAlert,
+}
+
+impl OtpPurpose {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OtpPurpose::PasswordReset => "password_reset",
+            OtpPurpose::EmailVerification => "email_verification",
+            OtpPurpose::TwoFactorLogin => "two_factor_login",
+            OtpPurpose::EmailChange => "email_change",
+            OtpPurpose::PhoneVerification => "phone_verification",
+            OtpPurpose::MagicLink => "magic_link",
+            OtpPurpose::LoginAlert => "login_alert",
+        }
+    }
+
+    pub fn expiration_minutes(&self) -> i32 {
+        match self {
+            OtpPurpose::PasswordReset => 5,
+            OtpPurpose::EmailVerification => 30,
+            OtpPurpose::TwoFactorLogin => 5,
+            OtpPurpose::EmailChange => 30,
+            OtpPurpose::PhoneVerification => 5,
+            OtpPurpose::MagicLink => 15,
+            //* Alerts are often read days later; the link has to outlive that
+            OtpPurpose::LoginAlert => 7 * 24 * 60,
+        }
+    }
+}
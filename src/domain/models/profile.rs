@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+
+/// Fields left out of the request keep their current value.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateProfilePayload {
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub title: Option<String>,
+    pub phone: Option<String>,
+    pub image: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangePasswordPayload {
+    pub current_password: String,
+    pub password: String,
+    pub password_confirmation: String,
+}
+
+/// First password for an account created through a sign-in provider.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetPasswordPayload {
+    pub password: String,
+    pub password_confirmation: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChangeEmailPayload {
+    pub email: String,
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfirmEmailChangePayload {
+    pub otp: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeleteAccountPayload {
+    pub password: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyPhonePayload {
+    pub otp: String,
+}
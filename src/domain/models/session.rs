@@ -0,0 +1,54 @@
+use actix_web::{web, HttpRequest};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{config::SecuritySettings, domain::middlewares::rate_limit::request_ip};
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: i64,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub expired_at: NaiveDateTime,
+    pub revoked_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ActiveSession {
+    pub id: Uuid,
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+    pub created_at: NaiveDateTime,
+    pub last_seen_at: NaiveDateTime,
+    pub is_current: bool,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SessionMetadata {
+    pub user_agent: Option<String>,
+    pub ip_address: Option<String>,
+}
+
+impl SessionMetadata {
+    pub fn from_request(req: &HttpRequest) -> Self {
+        let user_agent = req
+            .headers()
+            .get(actix_web::http::header::USER_AGENT)
+            .and_then(|value| value.to_str().ok())
+            .map(|value| value.chars().take(512).collect());
+        let trust_proxy_headers = req
+            .app_data::<web::Data<SecuritySettings>>()
+            .is_some_and(|settings| settings.trust_proxy_headers);
+        //* Sized for the ip_address columns, so a forged header can't make an insert fail
+        let ip_address = request_ip(req, trust_proxy_headers).map(|value| value.chars().take(45).collect());
+        Self {
+            user_agent,
+            ip_address,
+        }
+    }
+}
@@ -1,22 +1,48 @@
 use chrono::NaiveDateTime;
 use serde::{Deserialize, Serialize};
 use sqlx::{FromRow};
+use uuid::Uuid;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Claims {
+    pub id: i64,
+    pub first_name: String,
+    pub last_name: String,
+    pub email: String,
+    pub role_id: i32,
+    pub jti: Uuid,
+    pub iss: String,
+    pub aud: String,
+    pub iat: usize,
+    pub nbf: usize,
+    pub exp: usize,
+    /// Set when an admin is acting as this user (RFC 8693 `act` claim).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub act: Option<Actor>,
+}
+
+/// The user actually behind an impersonation token.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Actor {
+    pub sub: i64,
+}
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
-pub struct Token {
+pub struct RefreshToken {
     pub id: i64,
-    pub token: String,
+    pub user_id: i64,
+    pub session_id: Uuid,
+    pub token_hash: String,
+    pub is_used: bool,
     pub is_revoked: bool,
+    pub expired_at: NaiveDateTime,
     pub created_at: NaiveDateTime,
     pub updated_at: NaiveDateTime,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct TokenSigning {
-    pub id: i64,
-    pub first_name: String,
-    pub last_name: String,
-    pub email: String,
-    pub role_id: i32,
-    pub exp: usize,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthTokens {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub expires_in: i64,
 }
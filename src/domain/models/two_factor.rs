@@ -0,0 +1,42 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct UserTwoFactor {
+    pub user_id: i64,
+    pub secret: String,
+    pub enabled_at: Option<NaiveDateTime>,
+    pub last_used_step: Option<i64>,
+    pub created_at: NaiveDateTime,
+    pub updated_at: NaiveDateTime,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorSetup {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TwoFactorChallenge {
+    pub challenge_token: String,
+    pub expires_in: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorCodePayload {
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorDisablePayload {
+    pub password: String,
+    pub code: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TwoFactorLoginPayload {
+    pub challenge_token: String,
+    pub code: String,
+}
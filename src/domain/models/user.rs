@@ -1,6 +1,6 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
-use chrono::{DateTime, Utc};
+use chrono::NaiveDateTime;
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
 pub struct User {
@@ -9,10 +9,29 @@ pub struct User {
     pub last_name: String,
     pub username: String,
     pub email: String,
+    #[serde(skip_serializing)]
     pub password_hash: String,
     pub title: String,
-    pub image: String,
+    pub image: Option<String>,
     pub phone: String,
     pub role_id: i32,
-    pub deleted_at: Option<DateTime<Utc>>,
+    pub email_verified_at: Option<NaiveDateTime>,
+    pub deleted_at: Option<NaiveDateTime>,
+    pub phone_verified_at: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserQuery {
+    /// Matched against username, email and name
+    pub search: Option<String>,
+    pub role_id: Option<i32>,
+    #[serde(default)]
+    pub include_deleted: bool,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserRolePayload {
+    pub role_id: i32,
 }
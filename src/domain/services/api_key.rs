@@ -0,0 +1,164 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        errors::AppError,
+        models::{
+            api_key::{ApiKey, CreateApiKeyPayload},
+            user::User,
+        },
+        services::{ApiKeyService, PermissionService},
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::generator::{generate_token, hash_token},
+};
+
+/// Every key starts with this, so leaked keys are easy to recognise in logs and scanners.
+pub const API_KEY_PREFIX: &str = "kk_";
+pub const MAX_ACTIVE_API_KEYS: i64 = 20;
+
+const LOOKUP_PREFIX_LENGTH: usize = 12;
+const SECRET_LENGTH: usize = 40;
+
+#[async_trait]
+impl ApiKeyService for PostgresPool {
+    async fn create(&self, user: &User, data: &CreateApiKeyPayload) -> Result<(ApiKey, String), AppError> {
+        //* A key can never do more than its owner's role
+        let granted = PermissionService::for_role(self, user.role_id).await?;
+        let denied: Vec<&str> = data
+            .scopes
+            .iter()
+            .filter(|scope| !granted.contains(scope))
+            .map(String::as_str)
+            .collect();
+        if !denied.is_empty() {
+            return Err(AppError::ValidationError(format!(
+                "Scopes not available to your role: {}",
+                denied.join(", ")
+            )));
+        }
+        let mut scopes = data.scopes.clone();
+        scopes.sort();
+        scopes.dedup();
+
+        let mut hotel_ids = data.hotel_ids.clone();
+        hotel_ids.sort_unstable();
+        hotel_ids.dedup();
+        if !hotel_ids.is_empty() {
+            //* Only the tenant's own hotels; anyone else's are reported as missing
+            let found = sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM hotels WHERE id = ANY($1) AND user_id = $2")
+                .bind(&hotel_ids)
+                .bind(user.id)
+                .fetch_one(self.pool())
+                .await?;
+            if found != hotel_ids.len() as i64 {
+                return Err(AppError::NotFound("Hotel".into()));
+            }
+        }
+
+        let active = sqlx::query_scalar::<_, i64>(
+            "SELECT COUNT(*) FROM api_keys WHERE user_id = $1 AND revoked_at IS NULL AND (expired_at IS NULL OR expired_at > CURRENT_TIMESTAMP)",
+        )
+        .bind(user.id)
+        .fetch_one(self.pool())
+        .await?;
+        if active >= MAX_ACTIVE_API_KEYS {
+            return Err(AppError::ValidationError(format!(
+                "You can have at most {} active API keys",
+                MAX_ACTIVE_API_KEYS
+            )));
+        }
+
+        let prefix = generate_token(LOOKUP_PREFIX_LENGTH);
+        let key = format!("{}{}_{}", API_KEY_PREFIX, prefix, generate_token(SECRET_LENGTH));
+        let api_key = sqlx::query_as::<_, ApiKey>(
+            r#"
+            INSERT INTO api_keys (user_id, name, prefix, key_hash, scopes, hotel_ids, expired_at)
+            VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP + make_interval(days => $7))
+            RETURNING *
+            "#,
+        )
+        .bind(user.id)
+        .bind(data.name.trim())
+        .bind(&prefix)
+        .bind(hash_token(&key))
+        .bind(&scopes)
+        .bind(&hotel_ids)
+        .bind(data.expires_in_days)
+        .fetch_one(self.pool())
+        .await?;
+        Ok((api_key, key))
+    }
+
+    async fn list(&self, user_id: i64) -> Result<Vec<ApiKey>, sqlx::Error> {
+        sqlx::query_as::<_, ApiKey>("SELECT * FROM api_keys WHERE user_id = $1 ORDER BY created_at DESC")
+            .bind(user_id)
+            .fetch_all(self.pool())
+            .await
+    }
+
+    async fn revoke(&self, user_id: i64, key_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE api_keys SET revoked_at = CURRENT_TIMESTAMP WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(key_id)
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn authenticate(&self, key: &str, ip_address: Option<&str>) -> Result<Option<(ApiKey, User)>, sqlx::Error> {
+        let prefix = match key
+            .strip_prefix(API_KEY_PREFIX)
+            .and_then(|rest| rest.split_once('_'))
+        {
+            Some((prefix, _)) => prefix,
+            None => return Ok(None),
+        };
+        let api_key = sqlx::query_as::<_, ApiKey>(
+            "SELECT * FROM api_keys WHERE prefix = $1 AND revoked_at IS NULL AND (expired_at IS NULL OR expired_at > CURRENT_TIMESTAMP)",
+        )
+        .bind(prefix)
+        .fetch_optional(self.pool())
+        .await?;
+        let mut api_key = match api_key {
+            Some(api_key) if api_key.key_hash == hash_token(key) => api_key,
+            _ => return Ok(None),
+        };
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+            .bind(api_key.user_id)
+            .fetch_optional(self.pool())
+            .await?;
+        let user = match user {
+            Some(user) => user,
+            None => return Ok(None),
+        };
+
+        //* Whatever the key was created with, it only reaches hotels its owner has now: all of
+        //* them when unrestricted, otherwise the listed ones still owned
+        api_key.hotel_ids = sqlx::query_scalar::<_, i64>(
+            "SELECT id FROM hotels WHERE user_id = $1 AND (CARDINALITY($2::BIGINT[]) = 0 OR id = ANY($2)) ORDER BY id",
+        )
+        .bind(user.id)
+        .bind(&api_key.hotel_ids)
+        .fetch_all(self.pool())
+        .await?;
+
+        //* Record usage at most once a minute so busy integrations don't write on every call
+        sqlx::query(
+            "UPDATE api_keys SET last_used_at = CURRENT_TIMESTAMP, last_used_ip = $2 WHERE id = $1 AND (last_used_at IS NULL OR last_used_at < CURRENT_TIMESTAMP - INTERVAL '1 minute')",
+        )
+        .bind(api_key.id)
+        .bind(ip_address)
+        .execute(self.pool())
+        .await?;
+
+        Ok(Some((api_key, user)))
+    }
+}
+
+pub fn create_api_key_service(pool: PostgresPool) -> Box<dyn ApiKeyService> {
+    Box::new(pool)
+}
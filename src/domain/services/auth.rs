@@ -1,7 +1,33 @@
-use crate::domain::services::{otp::create_otp_service, user::create_user_service};
-use crate::infrastructure::email::send_mail;
-use crate::infrastructure::email_template::forgot_password::template;
-use crate::shared::utils::generator::generate_otp;
+use crate::config::{PasswordSettings, SecuritySettings};
+use crate::domain::errors::AppError;
+use crate::domain::models::auth::{
+    LoginAlertPayload, LoginOutcome, MagicLinkLoginPayload, MagicLinkPayload, OtpCheckPayload, ResendVerificationPayload,
+    ResetPasswordPayload, VerifyEmailPayload,
+};
+use crate::domain::models::auth_event::AuthEventKind;
+use crate::domain::models::otp::OtpPurpose;
+use crate::domain::models::session::SessionMetadata;
+use crate::domain::models::user::User;
+use crate::domain::services::{
+    lockout::create_lockout_service,
+    otp::{consume_otp, create_otp_service},
+    session::{revoke_all_sessions, start_session},
+    two_factor::create_two_factor_service,
+    user::create_user_service,
+};
+use crate::infrastructure::email::{send_mail, spawn_mail};
+use crate::infrastructure::email_template::{
+    email_verification, forgot_password::template, login_alert, magic_link,
+};
+use crate::domain::validations::{
+    auth_validations::ValidationError, password_validations::PasswordValidator,
+};
+use crate::shared::utils::{
+    generator::{generate_otp, generate_token, hash_token},
+    password::hash_password,
+    token_signing::TokenCodec,
+    user_agent::describe_user_agent,
+};
 use crate::{
     domain::{models::auth::ForgotPasswordPayload, services::AuthService},
     infrastructure::database::PostgresPool,
@@ -10,20 +36,317 @@ use async_trait::async_trait;
 
 #[async_trait]
 impl AuthService for PostgresPool {
-    async fn forgot_password(&self, data: &ForgotPasswordPayload) -> Result<(), Box<dyn std::error::Error>> {
+    async fn forgot_password(&self, security: &SecuritySettings, data: &ForgotPasswordPayload) -> Result<(), AppError> {
         let user_service = create_user_service(self.clone());
-        let user = user_service.find_by("email", &data.email).await?;
-        if user.is_none() {
-            return Err(Box::new(sqlx::Error::RowNotFound));
+        //* Unknown, unverified and deleted addresses get the same answer as known ones; whoever
+        //* registered an address they never proved mustn't have it sign anyone in
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) if user.deleted_at.is_none() && user.email_verified_at.is_some() => user,
+            _ => return Ok(()),
+        };
+
+        //* One email per cooldown; repeated requests reuse the code already sent
+        let recently_sent = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM otp_codes WHERE user_id = $1 AND purpose = $2 AND created_at > CURRENT_TIMESTAMP - make_interval(secs => $3))",
+        )
+        .bind(user.id)
+        .bind(OtpPurpose::PasswordReset.as_str())
+        .bind(security.forgot_password_cooldown_seconds as f64)
+        .fetch_one(self.pool())
+        .await?;
+        if recently_sent {
+            log::info!("Skipping password reset email for user {}: cooldown active", user.id);
+            return Ok(());
         }
-        let otp = generate_otp();
+
+        let otp = format!("{:06}", generate_otp());
+        let otp_service = create_otp_service(self.clone());
+        otp_service.create(user.id, OtpPurpose::PasswordReset, &otp).await?;
+        send_mail(user, "Forgot Password", template(&otp))
+            .await
+            .map_err(|e| {
+                log::error!("Failed to send password reset email: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        Ok(())
+    }
+
+    async fn check_otp(&self, data: &OtpCheckPayload) -> Result<(), AppError> {
+        let user_service = create_user_service(self.clone());
+        //* Unknown emails get the same answer as a wrong code
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) => user,
+            None => return Err(AppError::ValidationError("Invalid or expired OTP".into())),
+        };
         let otp_service = create_otp_service(self.clone());
-        otp_service.create(&otp.to_string()).await?;
-        send_mail(user.unwrap(), "Forgot Password", template(&otp.to_string())).await?;
+        otp_service.verify(user.id, OtpPurpose::PasswordReset, &data.otp).await?;
         Ok(())
     }
+
+    async fn reset_password(&self, passwords: &PasswordSettings, data: &ResetPasswordPayload) -> Result<(), AppError> {
+        let user_service = create_user_service(self.clone());
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) => user,
+            None => return Err(AppError::ValidationError("Invalid or expired OTP".into())),
+        };
+        let otp_service = create_otp_service(self.clone());
+        let otp = otp_service.verify(user.id, OtpPurpose::PasswordReset, &data.otp).await?;
+        //* Only after the code checks out, or the policy errors would tell which emails are registered
+        if let Err(ValidationError::Multiple(errors)) =
+            PasswordValidator::validate(passwords, &data.password, &user.username, &user.email)
+        {
+            return Err(AppError::ValidationError(format!("Validation failed: {}", errors.join(", "))));
+        }
+
+        let password_hash = hash_password(&data.password, passwords).await.map_err(|e| {
+            log::error!("Failed to hash password: {:?}", e);
+            AppError::InternalServerError
+        })?;
+
+        let mut tx = self.begin_transaction().await?;
+
+        //* Consume the code; a concurrent reset that got here first wins
+        if !consume_otp(&mut tx, otp.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired OTP".into()));
+        }
+
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(&password_hash)
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+
+        //* Sign the user out everywhere
+        revoke_all_sessions(&mut tx, user.id).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn send_email_verification(&self, data: &ResendVerificationPayload) -> Result<(), AppError> {
+        let user_service = create_user_service(self.clone());
+        //* Stay quiet about unknown or already verified addresses
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) if user.email_verified_at.is_none() && user.deleted_at.is_none() => user,
+            _ => return Ok(()),
+        };
+        let otp = format!("{:06}", generate_otp());
+        let otp_service = create_otp_service(self.clone());
+        otp_service.create(user.id, OtpPurpose::EmailVerification, &otp).await?;
+        send_mail(user, "Verify Your Email", email_verification::template(&otp))
+            .await
+            .map_err(|e| {
+                log::error!("Failed to send verification email: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        Ok(())
+    }
+
+    async fn verify_email(&self, data: &VerifyEmailPayload) -> Result<(), AppError> {
+        let user_service = create_user_service(self.clone());
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) => user,
+            None => return Err(AppError::ValidationError("Invalid or expired OTP".into())),
+        };
+        if user.email_verified_at.is_some() {
+            return Ok(());
+        }
+        let otp_service = create_otp_service(self.clone());
+        let otp = otp_service.verify(user.id, OtpPurpose::EmailVerification, &data.otp).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        if !consume_otp(&mut tx, otp.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired OTP".into()));
+        }
+        sqlx::query("UPDATE users SET email_verified_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn send_magic_link(&self, security: &SecuritySettings, data: &MagicLinkPayload) -> Result<(), AppError> {
+        let user_service = create_user_service(self.clone());
+        //* Unknown, unverified and deleted addresses get the same answer as known ones; whoever
+        //* registered an address they never proved mustn't have it sign anyone in
+        let user = match user_service.find_by("email", &data.email).await? {
+            Some(user) if user.deleted_at.is_none() && user.email_verified_at.is_some() => user,
+            _ => return Ok(()),
+        };
+
+        //* Limit links per address, not just per IP, so one inbox can't be flooded
+        let (recent, last_hour) = sqlx::query_as::<_, (bool, i64)>(
+            r#"
+            SELECT
+                COALESCE(BOOL_OR(created_at > CURRENT_TIMESTAMP - make_interval(secs => $3)), FALSE),
+                COUNT(*)
+            FROM otp_codes
+            WHERE user_id = $1 AND purpose = $2 AND created_at > CURRENT_TIMESTAMP - INTERVAL '1 hour'
+            "#,
+        )
+        .bind(user.id)
+        .bind(OtpPurpose::MagicLink.as_str())
+        .bind(security.magic_link_cooldown_seconds as f64)
+        .fetch_one(self.pool())
+        .await?;
+        if recent || last_hour >= security.magic_link_max_per_hour {
+            log::info!("Skipping login link email for user {}: rate limit reached", user.id);
+            return Ok(());
+        }
+
+        //* Only the hash is stored; the link itself is the credential
+        let token = generate_token(64);
+        let otp_service = create_otp_service(self.clone());
+        otp_service.create(user.id, OtpPurpose::MagicLink, &hash_token(&token)).await?;
+
+        let separator = if security.magic_link_url.contains('?') { '&' } else { '?' };
+        let link = format!("{}{}token={}", security.magic_link_url, separator, token);
+        send_mail(
+            user,
+            "Your Sign In Link",
+            magic_link::template(&link, OtpPurpose::MagicLink.expiration_minutes()),
+        )
+        .await
+        .map_err(|e| {
+            log::error!("Failed to send login link email: {:?}", e);
+            AppError::InternalServerError
+        })?;
+        Ok(())
+    }
+
+    async fn login_with_magic_link(&self, codec: &TokenCodec, security: &SecuritySettings, data: &MagicLinkLoginPayload, metadata: &SessionMetadata) -> Result<LoginOutcome, AppError> {
+        let otp_service = create_otp_service(self.clone());
+        let link = otp_service
+            .find_active_by_code(OtpPurpose::MagicLink, &hash_token(&data.token))
+            .await?;
+        let link = match link {
+            Some(link) => link,
+            None => return Err(AppError::ValidationError("Invalid or expired login link".into())),
+        };
+        let user_id = link.user_id.ok_or(AppError::Unauthorized)?;
+        let user = create_user_service(self.clone())
+            .find(user_id)
+            .await?
+            .filter(|user| user.deleted_at.is_none())
+            .ok_or(AppError::Unauthorized)?;
+
+        //* A locked account stays locked, whichever way the user signs in
+        create_lockout_service(self.clone()).ensure_unlocked(user.id).await?;
+        let two_factor_required = create_two_factor_service(self.clone()).is_enabled(user.id).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        //* Single use; a concurrent request with the same link that got here first wins
+        if !consume_otp(&mut tx, link.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired login link".into()));
+        }
+        if two_factor_required {
+            tx.commit().await?;
+            let challenge = create_two_factor_service(self.clone()).create_challenge(user.id).await?;
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
+        let tokens = start_session(&mut tx, codec, &user, metadata).await?;
+        tx.commit().await?;
+        if let Err(e) = AuthService::send_login_alert(self, security, &user, metadata).await {
+            log::error!("Failed to send login alert for user {}: {:?}", user.id, e);
+        }
+        Ok(LoginOutcome::Authenticated(Box::new(user), tokens))
+    }
+
+    async fn send_login_alert(&self, security: &SecuritySettings, user: &User, metadata: &SessionMetadata) -> Result<(), AppError> {
+        if !security.login_alerts {
+            return Ok(());
+        }
+        //* Known devices come from earlier sign-ins in the audit log; admins acting as the user don't count
+        let (has_history, known_device, known_ip) = sqlx::query_as::<_, (bool, bool, bool)>(
+            r#"
+            SELECT
+                COUNT(*) > 0,
+                COALESCE(BOOL_OR(user_agent IS NOT DISTINCT FROM $3), FALSE),
+                COALESCE(BOOL_OR(ip_address IS NOT DISTINCT FROM $4), FALSE)
+            FROM auth_events
+            WHERE user_id = $1 AND event = $2 AND outcome = 'success' AND actor_id IS NULL
+            "#,
+        )
+        .bind(user.id)
+        .bind(AuthEventKind::Login.as_str())
+        .bind(&metadata.user_agent)
+        .bind(&metadata.ip_address)
+        .fetch_one(self.pool())
+        .await?;
+        //* The first sign-in after registering has nothing to compare against
+        if !has_history || (known_device && known_ip) {
+            return Ok(());
+        }
+
+        //* Inserted directly: a later alert must not void the link in an earlier one
+        let token = generate_token(64);
+        sqlx::query(
+            "INSERT INTO otp_codes (user_id, purpose, code, expired_at) VALUES ($1, $2, $3, CURRENT_TIMESTAMP + make_interval(mins => $4))",
+        )
+        .bind(user.id)
+        .bind(OtpPurpose::LoginAlert.as_str())
+        .bind(hash_token(&token))
+        .bind(OtpPurpose::LoginAlert.expiration_minutes())
+        .execute(self.pool())
+        .await?;
+
+        let separator = if security.login_alert_url.contains('?') { '&' } else { '?' };
+        let link = format!("{}{}token={}", security.login_alert_url, separator, token);
+        let time = chrono::Utc::now().format("%d %b %Y, %H:%M UTC").to_string();
+        let device = describe_user_agent(metadata.user_agent.as_deref());
+        let ip_address = metadata.ip_address.as_deref().unwrap_or("Unknown");
+        //* The sign-in doesn't wait on the mail server
+        spawn_mail(
+            user.clone(),
+            "New Sign In to Your Account",
+            login_alert::template(&time, &device, ip_address, &link),
+        );
+        Ok(())
+    }
+
+    async fn deny_login(&self, security: &SecuritySettings, data: &LoginAlertPayload) -> Result<(User, u64), AppError> {
+        let otp_service = create_otp_service(self.clone());
+        let alert = otp_service
+            .find_active_by_code(OtpPurpose::LoginAlert, &hash_token(&data.token))
+            .await?
+            .ok_or_else(|| AppError::ValidationError("Invalid or expired link".into()))?;
+        let user_id = alert.user_id.ok_or(AppError::Unauthorized)?;
+        let user = create_user_service(self.clone())
+            .find(user_id)
+            .await?
+            .filter(|user| user.deleted_at.is_none())
+            .ok_or(AppError::Unauthorized)?;
+
+        //* Send the reset code first, so a mail failure leaves the link usable for another try
+        let reset = ForgotPasswordPayload {
+            email: user.email.clone(),
+        };
+        self.forgot_password(security, &reset).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        if !consume_otp(&mut tx, alert.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired link".into()));
+        }
+        //* One report covers every alert still waiting in the inbox
+        sqlx::query(
+            "UPDATE otp_codes SET is_active = FALSE, used_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND purpose = $2 AND is_active = TRUE",
+        )
+        .bind(user.id)
+        .bind(OtpPurpose::LoginAlert.as_str())
+        .execute(&mut *tx)
+        .await?;
+        let revoked = revoke_all_sessions(&mut tx, user.id).await?;
+        tx.commit().await?;
+        log::warn!("User {} reported a sign in they didn't make; revoked {} sessions", user.id, revoked);
+        Ok((user, revoked))
+    }
 }
 
 pub fn create_auth_service(pool: PostgresPool) -> Box<dyn AuthService> {
     Box::new(pool)
-}
\ No newline at end of file
+}
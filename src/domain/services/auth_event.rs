@@ -0,0 +1,99 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        models::auth_event::{AuthEvent, AuthEventQuery, NewAuthEvent},
+        services::AuthEventService,
+    },
+    infrastructure::database::PostgresPool,
+};
+
+#[async_trait]
+impl AuthEventService for PostgresPool {
+    async fn record(&self, event: &NewAuthEvent) -> Result<(), sqlx::Error> {
+        //* A failed sign-in only knows what was typed, so attach it to the account it names;
+        //* what was typed is only kept when it names no account
+        sqlx::query(
+            r#"
+            WITH resolved AS (
+                SELECT COALESCE($1, (SELECT id FROM users WHERE LOWER(username) = LOWER($5) OR LOWER(email) = LOWER($5) LIMIT 1)) AS user_id
+            )
+            INSERT INTO auth_events (user_id, actor_id, event, outcome, identifier, detail, ip_address, user_agent)
+            SELECT user_id, $2, $3, $4, CASE WHEN user_id IS NULL THEN $5 END, $6, $7, $8
+            FROM resolved
+            "#,
+        )
+        .bind(event.user_id)
+        .bind(event.actor_id)
+        .bind(event.kind.as_str())
+        .bind(event.outcome())
+        .bind(&event.identifier)
+        .bind(&event.detail)
+        .bind(&event.metadata.ip_address)
+        .bind(&event.metadata.user_agent)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn list(&self, query: &AuthEventQuery, limit: i64, offset: i64) -> Result<(Vec<AuthEvent>, i64), sqlx::Error> {
+        let filter = r#"
+            WHERE ($1::BIGINT IS NULL OR user_id = $1)
+            AND ($2::BIGINT IS NULL OR actor_id = $2)
+            AND ($3::TEXT IS NULL OR event = $3)
+            AND ($4::TEXT IS NULL OR outcome = $4)
+            AND ($5::TEXT IS NULL OR ip_address = $5)
+            AND ($6::TIMESTAMP IS NULL OR created_at >= $6)
+            AND ($7::TIMESTAMP IS NULL OR created_at < $7)
+        "#;
+
+        let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM auth_events {}", filter))
+            .bind(query.user_id)
+            .bind(query.actor_id)
+            .bind(&query.event)
+            .bind(&query.outcome)
+            .bind(&query.ip_address)
+            .bind(query.from)
+            .bind(query.to)
+            .fetch_one(self.pool())
+            .await?;
+        let events = sqlx::query_as::<_, AuthEvent>(&format!(
+            "SELECT * FROM auth_events {} ORDER BY created_at DESC, id DESC LIMIT $8 OFFSET $9",
+            filter
+        ))
+        .bind(query.user_id)
+        .bind(query.actor_id)
+        .bind(&query.event)
+        .bind(&query.outcome)
+        .bind(&query.ip_address)
+        .bind(query.from)
+        .bind(query.to)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool())
+        .await?;
+        Ok((events, total))
+    }
+
+    async fn recent_for_user(&self, user_id: i64, limit: i64) -> Result<Vec<AuthEvent>, sqlx::Error> {
+        sqlx::query_as::<_, AuthEvent>(
+            "SELECT * FROM auth_events WHERE user_id = $1 ORDER BY created_at DESC, id DESC LIMIT $2",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await
+    }
+}
+
+/// Writes an event without failing the request it describes; a lost audit row is logged instead.
+pub async fn record_auth_event(pool: &PostgresPool, event: NewAuthEvent) {
+    let auth_event_service = create_auth_event_service(pool.clone());
+    if let Err(e) = auth_event_service.record(&event).await {
+        log::error!("Failed to record {} auth event: {:?}", event.kind.as_str(), e);
+    }
+}
+
+pub fn create_auth_event_service(pool: PostgresPool) -> Box<dyn AuthEventService> {
+    Box::new(pool)
+}
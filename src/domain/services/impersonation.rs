@@ -0,0 +1,124 @@
+use async_trait::async_trait;
+use chrono::Duration;
+use uuid::Uuid;
+
+use crate::{
+    config::SecuritySettings,
+    domain::{
+        errors::AppError,
+        models::{
+            impersonation::{ImpersonatePayload, Impersonation, ImpersonationQuery, ImpersonationRequest},
+            session::SessionMetadata,
+            user::User,
+        },
+        services::{ImpersonationService, PermissionService},
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::token_signing::TokenCodec,
+};
+
+pub const IMPERSONATE_PERMISSION: &str = "user:impersonate";
+
+#[async_trait]
+impl ImpersonationService for PostgresPool {
+    async fn start(&self, codec: &TokenCodec, security: &SecuritySettings, actor: &User, user_id: i64, data: &ImpersonatePayload, metadata: &SessionMetadata) -> Result<(Impersonation, String), AppError> {
+        if user_id == actor.id {
+            return Err(AppError::ValidationError("You cannot impersonate yourself".into()));
+        }
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User".into()))?;
+        //* Staff who can impersonate can't be impersonated, or the trail could be laundered
+        if PermissionService::for_role(self, user.role_id)
+            .await?
+            .iter()
+            .any(|permission| permission == IMPERSONATE_PERMISSION)
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        let session_id = Uuid::new_v4();
+        let mut tx = self.begin_transaction().await?;
+        //* A plain session row keeps revocation and logout working; no refresh token is issued
+        sqlx::query(
+            "INSERT INTO sessions (id, user_id, user_agent, ip_address, expired_at) VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP + make_interval(mins => $5))",
+        )
+        .bind(session_id)
+        .bind(user.id)
+        .bind(&metadata.user_agent)
+        .bind(&metadata.ip_address)
+        .bind(security.impersonation_minutes)
+        .execute(&mut *tx)
+        .await?;
+        let impersonation = sqlx::query_as::<_, Impersonation>(
+            r#"
+            INSERT INTO impersonations (session_id, actor_id, user_id, reason, ip_address, expired_at)
+            SELECT id, $2, user_id, $3, ip_address, expired_at FROM sessions WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(session_id)
+        .bind(actor.id)
+        .bind(data.reason.trim())
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+
+        let token = codec
+            .sign_impersonation(&user, session_id, actor.id, Duration::minutes(security.impersonation_minutes as i64))
+            .map_err(|e| {
+                log::error!("Failed to sign impersonation token: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        log::warn!(
+            "User {} started impersonating user {} (session {}): {}",
+            actor.id,
+            user.id,
+            session_id,
+            impersonation.reason
+        );
+        Ok((impersonation, token))
+    }
+
+    async fn record_request(&self, session_id: Uuid, method: &str, path: &str, ip_address: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT INTO impersonation_requests (session_id, method, path, ip_address) VALUES ($1, $2, $3, $4)")
+            .bind(session_id)
+            .bind(method)
+            .bind(path)
+            .bind(ip_address)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+
+    async fn list(&self, query: &ImpersonationQuery, limit: i64) -> Result<Vec<Impersonation>, sqlx::Error> {
+        sqlx::query_as::<_, Impersonation>(
+            r#"
+            SELECT * FROM impersonations
+            WHERE ($1::BIGINT IS NULL OR actor_id = $1) AND ($2::BIGINT IS NULL OR user_id = $2)
+            ORDER BY created_at DESC
+            LIMIT $3
+            "#,
+        )
+        .bind(query.actor_id)
+        .bind(query.user_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await
+    }
+
+    async fn list_requests(&self, session_id: Uuid) -> Result<Vec<ImpersonationRequest>, sqlx::Error> {
+        sqlx::query_as::<_, ImpersonationRequest>(
+            "SELECT * FROM impersonation_requests WHERE session_id = $1 ORDER BY created_at, id",
+        )
+        .bind(session_id)
+        .fetch_all(self.pool())
+        .await
+    }
+}
+
+pub fn create_impersonation_service(pool: PostgresPool) -> Box<dyn ImpersonationService> {
+    Box::new(pool)
+}
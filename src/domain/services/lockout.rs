@@ -0,0 +1,149 @@
+use async_trait::async_trait;
+
+use crate::{
+    config::SecuritySettings,
+    domain::{
+        errors::AppError,
+        models::{
+            lockout::{AccountLockout, LockoutEvent},
+            session::SessionMetadata,
+        },
+        services::LockoutService,
+    },
+    infrastructure::database::PostgresPool,
+};
+
+#[async_trait]
+impl LockoutService for PostgresPool {
+    async fn ensure_unlocked(&self, user_id: i64) -> Result<(), AppError> {
+        let remaining = sqlx::query_scalar::<_, i64>(
+            "SELECT CEIL(EXTRACT(EPOCH FROM locked_until - CURRENT_TIMESTAMP))::BIGINT FROM account_lockouts WHERE user_id = $1 AND locked_until > CURRENT_TIMESTAMP",
+        )
+        .bind(user_id)
+        .fetch_optional(self.pool())
+        .await?;
+        match remaining {
+            Some(seconds) => Err(AppError::TooManyRequests(seconds.max(1) as u64)),
+            None => Ok(()),
+        }
+    }
+
+    async fn record_failure(
+        &self,
+        settings: &SecuritySettings,
+        user_id: i64,
+        metadata: &SessionMetadata,
+    ) -> Result<Option<u64>, sqlx::Error> {
+        let mut tx = self.begin_transaction().await?;
+
+        //* Failures outside the window start a fresh count
+        let lockout = sqlx::query_as::<_, AccountLockout>(
+            r#"
+            INSERT INTO account_lockouts (user_id, failed_attempts, last_failed_at)
+            VALUES ($1, 1, CURRENT_TIMESTAMP)
+            ON CONFLICT (user_id) DO UPDATE SET
+                failed_attempts = CASE
+                    WHEN account_lockouts.last_failed_at < CURRENT_TIMESTAMP - make_interval(mins => $2) THEN 1
+                    ELSE account_lockouts.failed_attempts + 1
+                END,
+                last_failed_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(settings.failure_window_minutes)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if lockout.failed_attempts < settings.lockout_threshold {
+            tx.commit().await?;
+            return Ok(None);
+        }
+
+        //* Each lockout in a row doubles the wait
+        let duration = settings.lockout_duration(lockout.lockout_count);
+        let locked_until = sqlx::query_scalar::<_, chrono::NaiveDateTime>(
+            r#"
+            UPDATE account_lockouts
+            SET failed_attempts = 0,
+                lockout_count = lockout_count + 1,
+                locked_until = CURRENT_TIMESTAMP + make_interval(secs => $2),
+                updated_at = CURRENT_TIMESTAMP
+            WHERE user_id = $1
+            RETURNING locked_until
+            "#,
+        )
+        .bind(user_id)
+        .bind(duration as f64)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "INSERT INTO account_lockout_events (user_id, event, failed_attempts, locked_until, ip_address) VALUES ($1, 'locked', $2, $3, $4)",
+        )
+        .bind(user_id)
+        .bind(lockout.failed_attempts)
+        .bind(locked_until)
+        .bind(&metadata.ip_address)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        log::warn!("Account {} locked for {} seconds after repeated failed sign-ins", user_id, duration);
+        Ok(Some(duration))
+    }
+
+    async fn reset(&self, user_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE account_lockouts SET failed_attempts = 0, lockout_count = 0, updated_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND (failed_attempts > 0 OR lockout_count > 0)",
+        )
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn unlock(&self, user_id: i64, actor_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.begin_transaction().await?;
+        let result = sqlx::query(
+            "UPDATE account_lockouts SET failed_attempts = 0, lockout_count = 0, locked_until = NULL, updated_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND locked_until > CURRENT_TIMESTAMP",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+
+        sqlx::query("INSERT INTO account_lockout_events (user_id, event, actor_id) VALUES ($1, 'unlocked', $2)")
+            .bind(user_id)
+            .bind(actor_id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn find(&self, user_id: i64) -> Result<Option<AccountLockout>, sqlx::Error> {
+        sqlx::query_as::<_, AccountLockout>("SELECT * FROM account_lockouts WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await
+    }
+
+    async fn list_events(&self, user_id: Option<i64>, limit: i64) -> Result<Vec<LockoutEvent>, sqlx::Error> {
+        sqlx::query_as::<_, LockoutEvent>(
+            "SELECT * FROM account_lockout_events WHERE ($1::BIGINT IS NULL OR user_id = $1) ORDER BY created_at DESC, id DESC LIMIT $2",
+        )
+        .bind(user_id)
+        .bind(limit)
+        .fetch_all(self.pool())
+        .await
+    }
+}
+
+pub fn create_lockout_service(pool: PostgresPool) -> Box<dyn LockoutService> {
+    Box::new(pool)
+}
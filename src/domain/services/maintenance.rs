@@ -0,0 +1,75 @@
+use async_trait::async_trait;
+
+use crate::{
+    config::MaintenanceSettings,
+    domain::{models::maintenance::PurgeReport, services::MaintenanceService},
+    infrastructure::database::PostgresPool,
+};
+
+#[async_trait]
+impl MaintenanceService for PostgresPool {
+    async fn purge_expired(&self, settings: &MaintenanceSettings) -> Result<PurgeReport, sqlx::Error> {
+        //* Tokens of sessions about to go are counted here rather than vanishing by cascade
+        let refresh_tokens = sqlx::query(
+            r#"
+            DELETE FROM refresh_tokens
+            WHERE expired_at < CURRENT_TIMESTAMP - make_interval(hours => $1)
+               OR session_id IN (
+                   SELECT id FROM sessions
+                   WHERE COALESCE(LEAST(revoked_at, expired_at), expired_at) < CURRENT_TIMESTAMP - make_interval(hours => $1)
+               )
+            "#,
+        )
+        .bind(settings.retention_hours)
+        .execute(self.pool())
+        .await?
+        .rows_affected();
+
+        let sessions = sqlx::query(
+            "DELETE FROM sessions WHERE COALESCE(LEAST(revoked_at, expired_at), expired_at) < CURRENT_TIMESTAMP - make_interval(hours => $1)",
+        )
+        .bind(settings.retention_hours)
+        .execute(self.pool())
+        .await?
+        .rows_affected();
+
+        //* Used, burnt and superseded codes are inactive; the rest only once expired
+        let otp_codes = sqlx::query(
+            r#"
+            DELETE FROM otp_codes
+            WHERE expired_at < CURRENT_TIMESTAMP - make_interval(hours => $1)
+               OR (is_active = FALSE AND COALESCE(used_at, created_at) < CURRENT_TIMESTAMP - make_interval(hours => $1))
+            "#,
+        )
+        .bind(settings.retention_hours)
+        .execute(self.pool())
+        .await?
+        .rows_affected();
+
+        //* Abandoned provider round trips are useless once expired
+        let oidc_login_states = sqlx::query("DELETE FROM oidc_login_states WHERE expired_at < CURRENT_TIMESTAMP")
+            .execute(self.pool())
+            .await?
+            .rows_affected();
+
+        //* The audit trail can't be deleted from, only stripped of personal data
+        let auth_events = sqlx::query_scalar::<_, i64>(
+            "SELECT anonymize_auth_events(NULL, NULL, (CURRENT_TIMESTAMP - make_interval(days => $1))::TIMESTAMP)",
+        )
+        .bind(settings.auth_event_retention_days)
+        .fetch_one(self.pool())
+        .await? as u64;
+
+        Ok(PurgeReport {
+            sessions,
+            refresh_tokens,
+            otp_codes,
+            oidc_login_states,
+            auth_events,
+        })
+    }
+}
+
+pub fn create_maintenance_service(pool: PostgresPool) -> Box<dyn MaintenanceService> {
+    Box::new(pool)
+}
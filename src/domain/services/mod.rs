@@ -1,35 +1,186 @@
+pub mod api_key;
 pub mod auth;
+pub mod auth_event;
+pub mod impersonation;
+pub mod lockout;
+pub mod maintenance;
+pub mod oidc;
 pub mod otp;
+pub mod permission;
+pub mod profile;
+pub mod role;
+pub mod session;
 pub mod token;
+pub mod two_factor;
 pub mod user;
 
-use crate::domain::models::{
-    auth::{ForgotPasswordPayload, LoginPayload, RegisterPayload},
-    token::Token,
-    user::User,
+use crate::config::{
+    MaintenanceSettings, OidcSettings, PasswordSettings, SecuritySettings, SmsSettings, UploadSettings,
 };
+use crate::domain::{
+    errors::AppError,
+    models::{
+        api_key::{ApiKey, CreateApiKeyPayload},
+        auth::{
+            ForgotPasswordPayload, LoginAlertPayload, LoginOutcome, LoginPayload, MagicLinkLoginPayload, MagicLinkPayload,
+            OtpCheckPayload, RegisterPayload, ResendVerificationPayload, ResetPasswordPayload,
+            VerifyEmailPayload,
+        },
+        auth_event::{AuthEvent, AuthEventQuery, NewAuthEvent},
+        impersonation::{ImpersonatePayload, Impersonation, ImpersonationQuery, ImpersonationRequest},
+        lockout::{AccountLockout, LockoutEvent},
+        maintenance::PurgeReport,
+        oidc::{OidcCallbackQuery, OidcOutcome, UserIdentity},
+        otp::{Otp, OtpPurpose},
+        permission::{CreatePermissionPayload, Permission},
+        profile::{ChangeEmailPayload, ChangePasswordPayload, ConfirmEmailChangePayload, DeleteAccountPayload, SetPasswordPayload, UpdateProfilePayload, VerifyPhonePayload},
+        role::Role,
+        session::{ActiveSession, SessionMetadata},
+        token::AuthTokens,
+        two_factor::{TwoFactorChallenge, TwoFactorDisablePayload, TwoFactorLoginPayload, TwoFactorSetup},
+        user::{User, UserQuery},
+    },
+};
+use crate::infrastructure::{oidc::OidcClient, sms::SmsSender};
+use crate::shared::utils::{avatar::ProcessedAvatar, token_signing::TokenCodec};
 use async_trait::async_trait;
+use uuid::Uuid;
 
 #[async_trait]
 pub trait UserService: Send {
     async fn find_by(&self, field: &str, value: &str) -> Result<Option<User>, sqlx::Error>;
-    async fn create(&self, user: &RegisterPayload) -> Result<(User, String), sqlx::Error>;
-    async fn login(&self, data: &LoginPayload) -> Result<(User, String), sqlx::Error>;
+    async fn create(&self, codec: &TokenCodec, passwords: &PasswordSettings, user: &RegisterPayload, metadata: &SessionMetadata) -> Result<(User, AuthTokens), sqlx::Error>;
+    async fn login(&self, codec: &TokenCodec, security: &SecuritySettings, passwords: &PasswordSettings, data: &LoginPayload, metadata: &SessionMetadata) -> Result<LoginOutcome, AppError>;
+    async fn find(&self, user_id: i64) -> Result<Option<User>, sqlx::Error>;
+    async fn list(&self, query: &UserQuery, limit: i64, offset: i64) -> Result<(Vec<User>, i64), sqlx::Error>;
+    async fn set_role(&self, user_id: i64, role_id: i32) -> Result<User, AppError>;
+    async fn soft_delete(&self, user_id: i64) -> Result<bool, sqlx::Error>;
+    async fn restore(&self, user_id: i64) -> Result<bool, sqlx::Error>;
 }
 
 #[async_trait]
 pub trait TokenService {
-    async fn create(&self, token: &str) -> Result<Token, sqlx::Error>;
-    async fn is_token_revoked(&self, token: &str) -> Result<bool, sqlx::Error>;
-    async fn revoke_token(&self, token: &str) -> Result<(), sqlx::Error>;
+    async fn refresh(&self, codec: &TokenCodec, refresh_token: &str, metadata: &SessionMetadata) -> Result<(User, AuthTokens), AppError>;
+}
+
+#[async_trait]
+pub trait SessionService {
+    async fn touch(&self, session_id: Uuid, user_id: i64) -> Result<bool, sqlx::Error>;
+    async fn list_active(&self, user_id: i64, current: Uuid) -> Result<Vec<ActiveSession>, sqlx::Error>;
+    async fn revoke(&self, user_id: i64, session_id: Uuid) -> Result<bool, sqlx::Error>;
+    async fn revoke_others(&self, user_id: i64, current: Uuid) -> Result<u64, sqlx::Error>;
+    async fn revoke_all(&self, user_id: i64) -> Result<u64, sqlx::Error>;
 }
 
 #[async_trait]
 pub trait AuthService {
-    async fn forgot_password(&self, data: &ForgotPasswordPayload) -> Result<(), Box<dyn std::error::Error>>;
+    async fn forgot_password(&self, security: &SecuritySettings, data: &ForgotPasswordPayload) -> Result<(), AppError>;
+    async fn check_otp(&self, data: &OtpCheckPayload) -> Result<(), AppError>;
+    async fn reset_password(&self, passwords: &PasswordSettings, data: &ResetPasswordPayload) -> Result<(), AppError>;
+    async fn send_email_verification(&self, data: &ResendVerificationPayload) -> Result<(), AppError>;
+    async fn verify_email(&self, data: &VerifyEmailPayload) -> Result<(), AppError>;
+    async fn send_magic_link(&self, security: &SecuritySettings, data: &MagicLinkPayload) -> Result<(), AppError>;
+    async fn login_with_magic_link(&self, codec: &TokenCodec, security: &SecuritySettings, data: &MagicLinkLoginPayload, metadata: &SessionMetadata) -> Result<LoginOutcome, AppError>;
+    async fn send_login_alert(&self, security: &SecuritySettings, user: &User, metadata: &SessionMetadata) -> Result<(), AppError>;
+    async fn deny_login(&self, security: &SecuritySettings, data: &LoginAlertPayload) -> Result<(User, u64), AppError>;
 }
 
 #[async_trait]
 pub trait OtpService: Send {
-    async fn create(&self, otp: &str) -> Result<(), sqlx::Error>;
-}
\ No newline at end of file
+    async fn create(&self, user_id: i64, purpose: OtpPurpose, otp: &str) -> Result<(), sqlx::Error>;
+    async fn verify(&self, user_id: i64, purpose: OtpPurpose, otp: &str) -> Result<Otp, AppError>;
+    async fn find_active_by_code(&self, purpose: OtpPurpose, code: &str) -> Result<Option<Otp>, sqlx::Error>;
+    /// Counts a guess against the code; false once its attempts are used up.
+    async fn take_attempt(&self, id: i64) -> Result<bool, sqlx::Error>;
+    async fn deactivate(&self, id: i64) -> Result<(), sqlx::Error>;
+}
+
+#[async_trait]
+pub trait TwoFactorService: Send {
+    async fn is_enabled(&self, user_id: i64) -> Result<bool, sqlx::Error>;
+    async fn enrollment_required(&self, user: &User) -> Result<bool, sqlx::Error>;
+    async fn setup(&self, user: &User) -> Result<TwoFactorSetup, AppError>;
+    async fn confirm(&self, user: &User, code: &str) -> Result<Vec<String>, AppError>;
+    async fn disable(&self, user: &User, data: &TwoFactorDisablePayload) -> Result<(), AppError>;
+    async fn regenerate_recovery_codes(&self, user: &User, code: &str) -> Result<Vec<String>, AppError>;
+    async fn create_challenge(&self, user_id: i64) -> Result<TwoFactorChallenge, sqlx::Error>;
+    async fn complete_login(&self, codec: &TokenCodec, security: &SecuritySettings, data: &TwoFactorLoginPayload, metadata: &SessionMetadata) -> Result<(User, AuthTokens), AppError>;
+}
+
+#[async_trait]
+pub trait RoleService {
+    async fn list(&self) -> Result<Vec<Role>, sqlx::Error>;
+    async fn find(&self, role_id: i32) -> Result<Option<Role>, sqlx::Error>;
+    async fn create(&self, name: &str) -> Result<Role, sqlx::Error>;
+    async fn delete(&self, role_id: i32) -> Result<(), AppError>;
+    async fn set_two_factor_requirement(&self, role_id: i32, required: bool) -> Result<Option<Role>, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait LockoutService: Send {
+    async fn ensure_unlocked(&self, user_id: i64) -> Result<(), AppError>;
+    async fn record_failure(&self, settings: &SecuritySettings, user_id: i64, metadata: &SessionMetadata) -> Result<Option<u64>, sqlx::Error>;
+    async fn reset(&self, user_id: i64) -> Result<(), sqlx::Error>;
+    async fn unlock(&self, user_id: i64, actor_id: i64) -> Result<bool, sqlx::Error>;
+    async fn find(&self, user_id: i64) -> Result<Option<AccountLockout>, sqlx::Error>;
+    async fn list_events(&self, user_id: Option<i64>, limit: i64) -> Result<Vec<LockoutEvent>, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait PermissionService {
+    async fn list(&self) -> Result<Vec<Permission>, sqlx::Error>;
+    async fn create(&self, data: &CreatePermissionPayload) -> Result<Permission, sqlx::Error>;
+    async fn for_role(&self, role_id: i32) -> Result<Vec<String>, sqlx::Error>;
+    async fn grant(&self, role_id: i32, permission: &str) -> Result<(), AppError>;
+    async fn revoke(&self, role_id: i32, permission: &str) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait ProfileService {
+    async fn update(&self, user_id: i64, data: &UpdateProfilePayload) -> Result<User, sqlx::Error>;
+    async fn change_password(&self, passwords: &PasswordSettings, user: &User, current_session: Uuid, data: &ChangePasswordPayload) -> Result<u64, AppError>;
+    async fn set_password(&self, passwords: &PasswordSettings, user: &User, current_session: Uuid, data: &SetPasswordPayload) -> Result<(), AppError>;
+    async fn request_email_change(&self, user: &User, data: &ChangeEmailPayload) -> Result<(), AppError>;
+    async fn confirm_email_change(&self, user: &User, data: &ConfirmEmailChangePayload) -> Result<User, AppError>;
+    async fn send_phone_verification(&self, sms: &dyn SmsSender, settings: &SmsSettings, user: &User) -> Result<(), AppError>;
+    async fn verify_phone(&self, user: &User, data: &VerifyPhonePayload) -> Result<User, AppError>;
+    async fn update_avatar(&self, uploads: &UploadSettings, user: &User, avatar: ProcessedAvatar) -> Result<User, AppError>;
+    async fn export_data(&self, user: &User) -> Result<serde_json::Value, sqlx::Error>;
+    async fn delete_account(&self, uploads: &UploadSettings, user: &User, data: &DeleteAccountPayload) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait OidcService {
+    async fn begin(&self, client: &OidcClient, settings: &OidcSettings, provider: &str, link_user_id: Option<i64>) -> Result<String, AppError>;
+    async fn complete(&self, client: &OidcClient, codec: &TokenCodec, settings: &OidcSettings, provider: &str, query: &OidcCallbackQuery, metadata: &SessionMetadata) -> Result<OidcOutcome, AppError>;
+    async fn list_identities(&self, user_id: i64) -> Result<Vec<UserIdentity>, sqlx::Error>;
+    async fn unlink(&self, user: &User, provider: &str) -> Result<(), AppError>;
+}
+
+#[async_trait]
+pub trait ApiKeyService {
+    async fn create(&self, user: &User, data: &CreateApiKeyPayload) -> Result<(ApiKey, String), AppError>;
+    async fn list(&self, user_id: i64) -> Result<Vec<ApiKey>, sqlx::Error>;
+    async fn revoke(&self, user_id: i64, key_id: i64) -> Result<bool, sqlx::Error>;
+    async fn authenticate(&self, key: &str, ip_address: Option<&str>) -> Result<Option<(ApiKey, User)>, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait ImpersonationService {
+    async fn start(&self, codec: &TokenCodec, security: &SecuritySettings, actor: &User, user_id: i64, data: &ImpersonatePayload, metadata: &SessionMetadata) -> Result<(Impersonation, String), AppError>;
+    async fn record_request(&self, session_id: Uuid, method: &str, path: &str, ip_address: Option<&str>) -> Result<(), sqlx::Error>;
+    async fn list(&self, query: &ImpersonationQuery, limit: i64) -> Result<Vec<Impersonation>, sqlx::Error>;
+    async fn list_requests(&self, session_id: Uuid) -> Result<Vec<ImpersonationRequest>, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait MaintenanceService {
+    async fn purge_expired(&self, settings: &MaintenanceSettings) -> Result<PurgeReport, sqlx::Error>;
+}
+
+#[async_trait]
+pub trait AuthEventService {
+    async fn record(&self, event: &NewAuthEvent) -> Result<(), sqlx::Error>;
+    async fn list(&self, query: &AuthEventQuery, limit: i64, offset: i64) -> Result<(Vec<AuthEvent>, i64), sqlx::Error>;
+    async fn recent_for_user(&self, user_id: i64, limit: i64) -> Result<Vec<AuthEvent>, sqlx::Error>;
+}
@@ -0,0 +1,344 @@
+use async_trait::async_trait;
+use sqlx::PgConnection;
+
+use crate::{
+    config::OidcSettings,
+    domain::{
+        errors::AppError,
+        models::{
+            auth::LoginOutcome,
+            oidc::{OidcCallbackQuery, OidcLoginState, OidcOutcome, UserIdentity},
+            session::SessionMetadata,
+            user::User,
+        },
+        services::{
+            lockout::create_lockout_service, session::start_session,
+            two_factor::create_two_factor_service, user::create_user_service, OidcService,
+        },
+    },
+    infrastructure::{
+        database::PostgresPool,
+        oidc::{IdTokenClaims, OidcClient, OidcError},
+    },
+    shared::utils::{
+        error_helpers::unique_violation_field,
+        generator::{generate_token, hash_token},
+        password::UNUSABLE_PASSWORD_HASH,
+        token_signing::TokenCodec,
+    },
+};
+
+/// Conflict resource for a provider email that matches an account whose address was never verified.
+pub const UNVERIFIED_ACCOUNT_CONFLICT: &str = "Unverified account";
+
+fn provider_error(e: OidcError) -> AppError {
+    match e {
+        OidcError::TokenExchange(_) | OidcError::InvalidIdToken(_) => {
+            log::warn!("OIDC sign-in rejected: {}", e);
+            AppError::ValidationError("Sign-in with the provider failed".into())
+        }
+        _ => {
+            log::error!("OIDC provider unavailable: {}", e);
+            AppError::InternalServerError
+        }
+    }
+}
+
+/// Username for a new account, taken from the email's local part.
+fn username_base(claims: &IdTokenClaims) -> String {
+    let base: String = claims
+        .email
+        .as_deref()
+        .and_then(|email| email.split('@').next())
+        .unwrap_or_default()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        .take(30)
+        .collect();
+    if base.is_empty() {
+        "user".into()
+    } else {
+        base
+    }
+}
+
+async fn insert_identity(
+    conn: &mut PgConnection,
+    user_id: i64,
+    provider: &str,
+    claims: &IdTokenClaims,
+) -> Result<UserIdentity, sqlx::Error> {
+    sqlx::query_as::<_, UserIdentity>(
+        "INSERT INTO user_identities (user_id, provider, subject, email, last_login_at) VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP) RETURNING *",
+    )
+    .bind(user_id)
+    .bind(provider)
+    .bind(&claims.sub)
+    .bind(&claims.email)
+    .fetch_one(&mut *conn)
+    .await
+}
+
+fn is_unique_violation(error: &sqlx::Error) -> bool {
+    matches!(error, sqlx::Error::Database(db_error) if db_error.is_unique_violation())
+}
+
+impl PostgresPool {
+    async fn find_identity(&self, provider: &str, subject: &str) -> Result<Option<UserIdentity>, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>("SELECT * FROM user_identities WHERE provider = $1 AND subject = $2")
+            .bind(provider)
+            .bind(subject)
+            .fetch_optional(self.pool())
+            .await
+    }
+
+    async fn link_identity(&self, user_id: i64, provider: &str, claims: &IdTokenClaims) -> Result<UserIdentity, AppError> {
+        match self.find_identity(provider, &claims.sub).await? {
+            Some(identity) if identity.user_id == user_id => return Ok(identity),
+            Some(_) => return Err(AppError::Conflict("Linked account".into())),
+            None => {}
+        }
+        let mut conn = self.pool().acquire().await?;
+        match insert_identity(&mut conn, user_id, provider, claims).await {
+            Ok(identity) => Ok(identity),
+            //* The user already has a different account at this provider
+            Err(e) if is_unique_violation(&e) => Err(AppError::Conflict("Linked account".into())),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Creates a customer account with no password for a first-time provider sign-in.
+    async fn create_oidc_user(&self, provider: &str, claims: &IdTokenClaims) -> Result<User, AppError> {
+        let base = username_base(claims);
+        let first_name = claims
+            .given_name
+            .clone()
+            .or_else(|| claims.name.clone())
+            .unwrap_or_default();
+        let last_name = claims.family_name.clone().unwrap_or_default();
+
+        for attempt in 0..5 {
+            let username = if attempt == 0 {
+                base.clone()
+            } else {
+                format!("{}_{}", base, generate_token(4).to_lowercase())
+            };
+
+            let mut tx = self.begin_transaction().await?;
+            let created = sqlx::query_as::<_, User>(
+                r#"
+                INSERT INTO users (first_name, last_name, phone, username, email, password_hash, title, role_id, email_verified_at)
+                VALUES ($1, $2, '', $3, $4, $5, '', (SELECT id FROM roles WHERE name = 'customer'), CURRENT_TIMESTAMP)
+                RETURNING *
+                "#,
+            )
+            .bind(&first_name)
+            .bind(&last_name)
+            .bind(&username)
+            .bind(&claims.email)
+            .bind(UNUSABLE_PASSWORD_HASH)
+            .fetch_one(&mut *tx)
+            .await;
+            let user = match created {
+                Ok(user) => user,
+                //* Taken username: try again with a suffix
+                Err(e) if unique_violation_field(&e) == Some("Username") => {
+                    tx.rollback().await?;
+                    continue;
+                }
+                Err(e) if unique_violation_field(&e) == Some("Email") => {
+                    return Err(AppError::Conflict("Email".into()))
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            sqlx::query("INSERT INTO balances (user_id, balance) VALUES ($1, 0)")
+                .bind(user.id)
+                .execute(&mut *tx)
+                .await?;
+            insert_identity(&mut tx, user.id, provider, claims).await?;
+            tx.commit().await?;
+            return Ok(user);
+        }
+        log::error!("Could not find a free username for OIDC user {}", base);
+        Err(AppError::InternalServerError)
+    }
+
+    async fn login_with_identity(
+        &self,
+        codec: &TokenCodec,
+        provider: &str,
+        claims: &IdTokenClaims,
+        metadata: &SessionMetadata,
+    ) -> Result<LoginOutcome, AppError> {
+        let user = match self.find_identity(provider, &claims.sub).await? {
+            Some(identity) => {
+                sqlx::query("UPDATE user_identities SET last_login_at = CURRENT_TIMESTAMP WHERE id = $1")
+                    .bind(identity.id)
+                    .execute(self.pool())
+                    .await?;
+                sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+                    .bind(identity.user_id)
+                    .fetch_optional(self.pool())
+                    .await?
+                    .ok_or(AppError::InvalidCredentials)?
+            }
+            None => {
+                //* Only an address the provider vouches for may claim an account
+                let email = match claims.email.as_deref() {
+                    Some(email) if claims.email_verified => email,
+                    _ => {
+                        return Err(AppError::ValidationError(
+                            "The provider did not return a verified email address".into(),
+                        ))
+                    }
+                };
+                let user_service = create_user_service(self.clone());
+                match user_service.find_by("email", email).await? {
+                    Some(user) if user.deleted_at.is_some() => return Err(AppError::InvalidCredentials),
+                    //* Whoever registered an unverified address may not own it; linking would let
+                    //* their password into the provider user's account. The owner links after signing in
+                    Some(user) if user.email_verified_at.is_none() => {
+                        return Err(AppError::Conflict(UNVERIFIED_ACCOUNT_CONFLICT.into()))
+                    }
+                    Some(user) => {
+                        self.link_identity(user.id, provider, claims).await?;
+                        user
+                    }
+                    None => self.create_oidc_user(provider, claims).await?,
+                }
+            }
+        };
+
+        //* A locked account stays locked, whichever way the user signs in
+        create_lockout_service(self.clone()).ensure_unlocked(user.id).await?;
+        let two_factor_service = create_two_factor_service(self.clone());
+        if two_factor_service.is_enabled(user.id).await? {
+            let challenge = two_factor_service.create_challenge(user.id).await?;
+            return Ok(LoginOutcome::TwoFactorRequired(challenge));
+        }
+        let mut tx = self.begin_transaction().await?;
+        let tokens = start_session(&mut tx, codec, &user, metadata).await?;
+        tx.commit().await?;
+        Ok(LoginOutcome::Authenticated(Box::new(user), tokens))
+    }
+}
+
+#[async_trait]
+impl OidcService for PostgresPool {
+    async fn begin(
+        &self,
+        client: &OidcClient,
+        settings: &OidcSettings,
+        provider: &str,
+        link_user_id: Option<i64>,
+    ) -> Result<String, AppError> {
+        let provider_settings = settings
+            .providers
+            .get(provider)
+            .ok_or_else(|| AppError::NotFound("Provider".into()))?;
+
+        let state = generate_token(32);
+        let nonce = generate_token(32);
+        let code_verifier = generate_token(64);
+        let url = client
+            .authorization_url(provider_settings, &state, &nonce, &code_verifier)
+            .await
+            .map_err(provider_error)?;
+
+        sqlx::query(
+            "INSERT INTO oidc_login_states (state_hash, provider, nonce, code_verifier, link_user_id, expired_at) VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP + make_interval(secs => $6))",
+        )
+        .bind(hash_token(&state))
+        .bind(provider)
+        .bind(&nonce)
+        .bind(&code_verifier)
+        .bind(link_user_id)
+        .bind(settings.state_ttl_seconds as f64)
+        .execute(self.pool())
+        .await?;
+        Ok(url)
+    }
+
+    async fn complete(
+        &self,
+        client: &OidcClient,
+        codec: &TokenCodec,
+        settings: &OidcSettings,
+        provider: &str,
+        query: &OidcCallbackQuery,
+        metadata: &SessionMetadata,
+    ) -> Result<OidcOutcome, AppError> {
+        if let Some(error) = &query.error {
+            log::info!(
+                "OIDC provider {} returned {}: {}",
+                provider,
+                error,
+                query.error_description.as_deref().unwrap_or("")
+            );
+            return Err(AppError::ValidationError("Sign-in with the provider was cancelled or refused".into()));
+        }
+        let (code, state) = match (query.code.as_deref(), query.state.as_deref()) {
+            (Some(code), Some(state)) if !code.is_empty() && !state.is_empty() => (code, state),
+            _ => return Err(AppError::ValidationError("Code and state are required".into())),
+        };
+        let provider_settings = settings
+            .providers
+            .get(provider)
+            .ok_or_else(|| AppError::NotFound("Provider".into()))?;
+
+        //* A state is good for one callback only
+        let login_state = sqlx::query_as::<_, OidcLoginState>(
+            "DELETE FROM oidc_login_states WHERE state_hash = $1 AND expired_at > CURRENT_TIMESTAMP RETURNING provider, nonce, code_verifier, link_user_id",
+        )
+        .bind(hash_token(state))
+        .fetch_optional(self.pool())
+        .await?;
+        let login_state = match login_state {
+            Some(login_state) if login_state.provider == provider => login_state,
+            _ => return Err(AppError::ValidationError("Invalid or expired sign-in state".into())),
+        };
+
+        let claims = client
+            .exchange_code(provider_settings, code, &login_state.code_verifier, &login_state.nonce)
+            .await
+            .map_err(provider_error)?;
+
+        match login_state.link_user_id {
+            Some(user_id) => Ok(OidcOutcome::Linked(self.link_identity(user_id, provider, &claims).await?)),
+            None => Ok(OidcOutcome::Login(
+                self.login_with_identity(codec, provider, &claims, metadata).await?,
+            )),
+        }
+    }
+
+    async fn list_identities(&self, user_id: i64) -> Result<Vec<UserIdentity>, sqlx::Error> {
+        sqlx::query_as::<_, UserIdentity>("SELECT * FROM user_identities WHERE user_id = $1 ORDER BY created_at")
+            .bind(user_id)
+            .fetch_all(self.pool())
+            .await
+    }
+
+    async fn unlink(&self, user: &User, provider: &str) -> Result<(), AppError> {
+        let identities = self.list_identities(user.id).await?;
+        if !identities.iter().any(|identity| identity.provider == provider) {
+            return Err(AppError::NotFound("Linked account".into()));
+        }
+        //* Keep at least one way to sign in
+        if user.password_hash == UNUSABLE_PASSWORD_HASH && identities.len() == 1 {
+            return Err(AppError::ValidationError(
+                "Set a password before unlinking your only sign-in method".into(),
+            ));
+        }
+        sqlx::query("DELETE FROM user_identities WHERE user_id = $1 AND provider = $2")
+            .bind(user.id)
+            .bind(provider)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+}
+
+pub fn create_oidc_service(pool: PostgresPool) -> Box<dyn OidcService> {
+    Box::new(pool)
+}
@@ -1,14 +1,106 @@
 use async_trait::async_trait;
+use sqlx::PgConnection;
 
-use crate::{domain::services::OtpService, infrastructure::database::PostgresPool};
+use crate::{
+    domain::{
+        errors::AppError,
+        models::otp::{Otp, OtpPurpose},
+        services::OtpService,
+    },
+    infrastructure::database::PostgresPool,
+};
+
+pub const OTP_MAX_ATTEMPTS: i32 = 5;
+
+/// Marks a verified code as used. Returns false if another request consumed it first.
+pub async fn consume_otp(conn: &mut PgConnection, id: i64) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE otp_codes SET is_active = FALSE, used_at = CURRENT_TIMESTAMP WHERE id = $1 AND is_active = TRUE",
+    )
+    .bind(id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(result.rows_affected() > 0)
+}
 
 #[async_trait]
 impl OtpService for PostgresPool {
-    async fn create(&self, otp: &str) -> Result<(), sqlx::Error> {
-        sqlx::query(format!("INSERT INTO otp_codes (code, expired_at) VALUES ($1, CURRENT_TIMESTAMP + INTERVAL '{} minute')", 5).as_str())
-        .bind(&otp)
-        .execute(self.pool())
+    async fn create(&self, user_id: i64, purpose: OtpPurpose, otp: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.begin_transaction().await?;
+
+        //* Only the latest code for a given purpose stays usable
+        sqlx::query("UPDATE otp_codes SET is_active = FALSE WHERE user_id = $1 AND purpose = $2 AND is_active = TRUE")
+            .bind(user_id)
+            .bind(purpose.as_str())
+            .execute(&mut *tx)
+            .await?;
+
+        sqlx::query(
+            "INSERT INTO otp_codes (user_id, purpose, code, expired_at) VALUES ($1, $2, $3, CURRENT_TIMESTAMP + make_interval(mins => $4))",
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .bind(otp)
+        .bind(purpose.expiration_minutes())
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn verify(&self, user_id: i64, purpose: OtpPurpose, otp: &str) -> Result<Otp, AppError> {
+        let record = sqlx::query_as::<_, Otp>(
+            "SELECT * FROM otp_codes WHERE user_id = $1 AND purpose = $2 AND is_active = TRUE AND expired_at > CURRENT_TIMESTAMP ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(user_id)
+        .bind(purpose.as_str())
+        .fetch_optional(self.pool())
         .await?;
+
+        let record = match record {
+            Some(record) => record,
+            None => return Err(AppError::ValidationError("Invalid or expired OTP".into())),
+        };
+
+        //* Count the guess before comparing, so concurrent guesses can't get past the cap together
+        if !self.take_attempt(record.id).await? || record.code.as_deref() != Some(otp) {
+            return Err(AppError::ValidationError("Invalid or expired OTP".into()));
+        }
+
+        Ok(record)
+    }
+
+    async fn find_active_by_code(&self, purpose: OtpPurpose, code: &str) -> Result<Option<Otp>, sqlx::Error> {
+        sqlx::query_as::<_, Otp>(
+            "SELECT * FROM otp_codes WHERE purpose = $1 AND code = $2 AND is_active = TRUE AND expired_at > CURRENT_TIMESTAMP",
+        )
+        .bind(purpose.as_str())
+        .bind(code)
+        .fetch_optional(self.pool())
+        .await
+    }
+
+    async fn take_attempt(&self, id: i64) -> Result<bool, sqlx::Error> {
+        //* Checked and counted in one statement; burn the code once the cap is used up
+        let attempts = sqlx::query_scalar::<_, i32>(
+            "UPDATE otp_codes SET attempts = attempts + 1 WHERE id = $1 AND is_active = TRUE AND attempts < $2 RETURNING attempts",
+        )
+        .bind(id)
+        .bind(OTP_MAX_ATTEMPTS)
+        .fetch_optional(self.pool())
+        .await?;
+        if attempts.is_none() {
+            self.deactivate(id).await?;
+        }
+        Ok(attempts.is_some())
+    }
+
+    async fn deactivate(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE otp_codes SET is_active = FALSE WHERE id = $1")
+            .bind(id)
+            .execute(self.pool())
+            .await?;
         Ok(())
     }
 }
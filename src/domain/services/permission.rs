@@ -0,0 +1,88 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{
+        errors::AppError,
+        models::permission::{CreatePermissionPayload, Permission},
+        services::PermissionService,
+    },
+    infrastructure::database::PostgresPool,
+};
+
+#[async_trait]
+impl PermissionService for PostgresPool {
+    async fn list(&self) -> Result<Vec<Permission>, sqlx::Error> {
+        sqlx::query_as::<_, Permission>("SELECT * FROM permissions ORDER BY name")
+            .fetch_all(self.pool())
+            .await
+    }
+
+    async fn create(&self, data: &CreatePermissionPayload) -> Result<Permission, sqlx::Error> {
+        sqlx::query_as::<_, Permission>(
+            "INSERT INTO permissions (name, description) VALUES ($1, $2) RETURNING *",
+        )
+        .bind(&data.name)
+        .bind(&data.description)
+        .fetch_one(self.pool())
+        .await
+    }
+
+    async fn for_role(&self, role_id: i32) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar::<_, String>(
+            r#"
+            SELECT p.name
+            FROM role_permissions rp
+            JOIN permissions p ON p.id = rp.permission_id
+            WHERE rp.role_id = $1
+            ORDER BY p.name
+            "#,
+        )
+        .bind(role_id)
+        .fetch_all(self.pool())
+        .await
+    }
+
+    async fn grant(&self, role_id: i32, permission: &str) -> Result<(), AppError> {
+        let permission_id = self.permission_id(role_id, permission).await?;
+        sqlx::query(
+            "INSERT INTO role_permissions (role_id, permission_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+        )
+        .bind(role_id)
+        .bind(permission_id)
+        .execute(self.pool())
+        .await?;
+        Ok(())
+    }
+
+    async fn revoke(&self, role_id: i32, permission: &str) -> Result<(), AppError> {
+        let permission_id = self.permission_id(role_id, permission).await?;
+        sqlx::query("DELETE FROM role_permissions WHERE role_id = $1 AND permission_id = $2")
+            .bind(role_id)
+            .bind(permission_id)
+            .execute(self.pool())
+            .await?;
+        Ok(())
+    }
+}
+
+impl PostgresPool {
+    /// Resolves a permission name, checking that both it and the role exist.
+    async fn permission_id(&self, role_id: i32, permission: &str) -> Result<i32, AppError> {
+        let role_exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM roles WHERE id = $1)")
+            .bind(role_id)
+            .fetch_one(self.pool())
+            .await?;
+        if !role_exists {
+            return Err(AppError::NotFound("Role".into()));
+        }
+        sqlx::query_scalar::<_, i32>("SELECT id FROM permissions WHERE name = $1")
+            .bind(permission)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or_else(|| AppError::NotFound("Permission".into()))
+    }
+}
+
+pub fn create_permission_service(pool: PostgresPool) -> Box<dyn PermissionService> {
+    Box::new(pool)
+}
@@ -0,0 +1,445 @@
+use async_trait::async_trait;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::{
+    config::{PasswordSettings, SmsSettings, UploadSettings},
+    domain::{
+        errors::AppError,
+        models::{
+            otp::OtpPurpose,
+            profile::{
+                ChangeEmailPayload, ChangePasswordPayload, ConfirmEmailChangePayload, DeleteAccountPayload,
+                SetPasswordPayload, UpdateProfilePayload, VerifyPhonePayload,
+            },
+            user::User,
+        },
+        services::{
+            otp::{consume_otp, create_otp_service},
+            user::create_user_service,
+            ProfileService,
+        },
+    },
+    infrastructure::{
+        database::PostgresPool, email::send_mail, email_template::email_verification, sms::SmsSender,
+        storage,
+    },
+    shared::utils::{
+        avatar::{thumbnail_url, ProcessedAvatar},
+        error_helpers::unique_violation_field,
+        generator::generate_otp,
+        password::{hash_password, verify_password, UNUSABLE_PASSWORD_HASH},
+        phone::normalize_phone,
+    },
+};
+
+/// How recent the sign-in must be for an account without a password to set one.
+const SET_PASSWORD_SIGN_IN_MINUTES: i32 = 10;
+
+impl PostgresPool {
+    /// Rows of `table` belonging to the user as a JSON array, oldest first.
+    async fn export_rows(&self, table: &str, user_id: i64) -> Result<serde_json::Value, sqlx::Error> {
+        let query = format!(
+            "SELECT COALESCE(json_agg(t ORDER BY t.id), '[]'::json) FROM {} t WHERE t.user_id = $1",
+            table
+        );
+        sqlx::query_scalar(&query)
+            .bind(user_id)
+            .fetch_one(self.pool())
+            .await
+    }
+
+    async fn check_password(&self, user: &User, password: &str) -> Result<(), AppError> {
+        let password_matches = verify_password(password, &user.password_hash)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to verify password: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        if !password_matches {
+            return Err(AppError::InvalidCredentials);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ProfileService for PostgresPool {
+    async fn update(&self, user_id: i64, data: &UpdateProfilePayload) -> Result<User, sqlx::Error> {
+        let phone = data
+            .phone
+            .as_deref()
+            .map(|phone| normalize_phone(phone).unwrap_or_else(|| phone.to_string()));
+
+        let mut tx = self.begin_transaction().await?;
+        //* A new number has to be verified again
+        let updated = sqlx::query_as::<_, User>(
+            r#"
+            UPDATE users SET
+                first_name = COALESCE($2, first_name),
+                last_name = COALESCE($3, last_name),
+                title = COALESCE($4, title),
+                phone = COALESCE($5, phone),
+                image = COALESCE($6, image),
+                phone_verified_at = CASE WHEN $5 IS NULL OR $5 = phone THEN phone_verified_at END
+            WHERE id = $1
+            RETURNING *
+            "#,
+        )
+        .bind(user_id)
+        .bind(data.first_name.as_deref().map(str::trim))
+        .bind(data.last_name.as_deref().map(str::trim))
+        .bind(data.title.as_deref().map(str::trim))
+        .bind(&phone)
+        .bind(data.image.as_deref().map(str::trim))
+        .fetch_one(&mut *tx)
+        .await?;
+        if phone.is_some() {
+            //* Codes sent to the previous number must not verify the new one
+            sqlx::query("UPDATE otp_codes SET is_active = FALSE WHERE user_id = $1 AND purpose = $2 AND is_active = TRUE")
+                .bind(user_id)
+                .bind(OtpPurpose::PhoneVerification.as_str())
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn change_password(
+        &self,
+        passwords: &PasswordSettings,
+        user: &User,
+        current_session: Uuid,
+        data: &ChangePasswordPayload,
+    ) -> Result<u64, AppError> {
+        self.check_password(user, &data.current_password).await?;
+
+        let password_hash = hash_password(&data.password, passwords).await.map_err(|e| {
+            log::error!("Failed to hash password: {:?}", e);
+            AppError::InternalServerError
+        })?;
+
+        let mut tx = self.begin_transaction().await?;
+        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+            .bind(&password_hash)
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+
+        //* Every other device has to sign in again with the new password
+        let revoked = sqlx::query(
+            "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND id <> $2 AND revoked_at IS NULL",
+        )
+        .bind(user.id)
+        .bind(current_session)
+        .execute(&mut *tx)
+        .await?
+        .rows_affected();
+
+        tx.commit().await?;
+        Ok(revoked)
+    }
+
+    async fn set_password(
+        &self,
+        passwords: &PasswordSettings,
+        user: &User,
+        current_session: Uuid,
+        data: &SetPasswordPayload,
+    ) -> Result<(), AppError> {
+        if user.password_hash != UNUSABLE_PASSWORD_HASH {
+            return Err(AppError::ValidationError(
+                "A password is already set; change it with your current password instead".into(),
+            ));
+        }
+        //* With no password to confirm, a fresh provider sign-in stands in for it
+        let recent_sign_in = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM sessions WHERE id = $1 AND created_at > CURRENT_TIMESTAMP - make_interval(mins => $2))",
+        )
+        .bind(current_session)
+        .bind(SET_PASSWORD_SIGN_IN_MINUTES)
+        .fetch_one(self.pool())
+        .await?;
+        if !recent_sign_in {
+            return Err(AppError::ValidationError(format!(
+                "Sign in with your provider again within the last {} minutes to set a password",
+                SET_PASSWORD_SIGN_IN_MINUTES
+            )));
+        }
+
+        let password_hash = hash_password(&data.password, passwords).await.map_err(|e| {
+            log::error!("Failed to hash password: {:?}", e);
+            AppError::InternalServerError
+        })?;
+        //* Only replaces the placeholder, so two concurrent requests can't both set one
+        let updated = sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2 AND password_hash = $3")
+            .bind(&password_hash)
+            .bind(user.id)
+            .bind(UNUSABLE_PASSWORD_HASH)
+            .execute(self.pool())
+            .await?
+            .rows_affected();
+        if updated == 0 {
+            return Err(AppError::ValidationError(
+                "A password is already set; change it with your current password instead".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn request_email_change(&self, user: &User, data: &ChangeEmailPayload) -> Result<(), AppError> {
+        self.check_password(user, &data.password).await?;
+
+        if data.email.eq_ignore_ascii_case(&user.email) {
+            return Err(AppError::ValidationError(
+                "New email must differ from the current one".into(),
+            ));
+        }
+        let user_service = create_user_service(self.clone());
+        if user_service.find_by("email", &data.email).await?.is_some() {
+            return Err(AppError::Conflict("Email".into()));
+        }
+
+        sqlx::query("UPDATE users SET pending_email = $1 WHERE id = $2")
+            .bind(&data.email)
+            .bind(user.id)
+            .execute(self.pool())
+            .await?;
+
+        let otp = format!("{:06}", generate_otp());
+        let otp_service = create_otp_service(self.clone());
+        otp_service.create(user.id, OtpPurpose::EmailChange, &otp).await?;
+
+        //* The code goes to the new address, proving the user can read it
+        let recipient = User {
+            email: data.email.clone(),
+            ..user.clone()
+        };
+        send_mail(recipient, "Verify Your New Email", email_verification::template(&otp))
+            .await
+            .map_err(|e| {
+                log::error!("Failed to send email change verification: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        Ok(())
+    }
+
+    async fn confirm_email_change(&self, user: &User, data: &ConfirmEmailChangePayload) -> Result<User, AppError> {
+        let pending_email: Option<String> = sqlx::query_scalar("SELECT pending_email FROM users WHERE id = $1")
+            .bind(user.id)
+            .fetch_one(self.pool())
+            .await?;
+        if pending_email.is_none() {
+            return Err(AppError::ValidationError("No email change is pending".into()));
+        }
+
+        let otp_service = create_otp_service(self.clone());
+        let otp = otp_service.verify(user.id, OtpPurpose::EmailChange, &data.otp).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        if !consume_otp(&mut tx, otp.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired OTP".into()));
+        }
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET email = pending_email, pending_email = NULL, email_verified_at = CURRENT_TIMESTAMP WHERE id = $1 AND pending_email IS NOT NULL RETURNING *",
+        )
+        .bind(user.id)
+        .fetch_optional(&mut *tx)
+        .await;
+        let updated = match updated {
+            Ok(Some(updated)) => updated,
+            Ok(None) => {
+                tx.rollback().await?;
+                return Err(AppError::ValidationError("No email change is pending".into()));
+            }
+            //* Someone registered the address after the code was sent
+            Err(e) if unique_violation_field(&e) == Some("Email") => {
+                tx.rollback().await?;
+                return Err(AppError::Conflict("Email".into()));
+            }
+            Err(e) => return Err(e.into()),
+        };
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn send_phone_verification(&self, sms: &dyn SmsSender, settings: &SmsSettings, user: &User) -> Result<(), AppError> {
+        if user.phone.is_empty() {
+            return Err(AppError::ValidationError("Add a phone number to your profile first".into()));
+        }
+        if user.phone_verified_at.is_some() {
+            return Err(AppError::ValidationError("Phone number is already verified".into()));
+        }
+
+        let recently_sent = sqlx::query_scalar::<_, bool>(
+            "SELECT EXISTS (SELECT 1 FROM otp_codes WHERE user_id = $1 AND purpose = $2 AND created_at > CURRENT_TIMESTAMP - make_interval(secs => $3))",
+        )
+        .bind(user.id)
+        .bind(OtpPurpose::PhoneVerification.as_str())
+        .bind(settings.resend_cooldown_seconds as f64)
+        .fetch_one(self.pool())
+        .await?;
+        if recently_sent {
+            return Err(AppError::TooManyRequests(settings.resend_cooldown_seconds as u64));
+        }
+
+        let otp = format!("{:06}", generate_otp());
+        let otp_service = create_otp_service(self.clone());
+        otp_service.create(user.id, OtpPurpose::PhoneVerification, &otp).await?;
+        let message = format!(
+            "Your Karcis verification code is {}. It expires in {} minutes. Don't share it with anyone.",
+            otp,
+            OtpPurpose::PhoneVerification.expiration_minutes()
+        );
+        sms.send(&user.phone, &message).await.map_err(|e| {
+            log::error!("Failed to send phone verification SMS: {:?}", e);
+            AppError::InternalServerError
+        })?;
+        Ok(())
+    }
+
+    async fn verify_phone(&self, user: &User, data: &VerifyPhonePayload) -> Result<User, AppError> {
+        if user.phone_verified_at.is_some() {
+            return Ok(user.clone());
+        }
+        let otp_service = create_otp_service(self.clone());
+        let otp = otp_service.verify(user.id, OtpPurpose::PhoneVerification, &data.otp).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        if !consume_otp(&mut tx, otp.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::ValidationError("Invalid or expired OTP".into()));
+        }
+        let updated = sqlx::query_as::<_, User>(
+            "UPDATE users SET phone_verified_at = CURRENT_TIMESTAMP WHERE id = $1 RETURNING *",
+        )
+        .bind(user.id)
+        .fetch_one(&mut *tx)
+        .await?;
+        tx.commit().await?;
+        Ok(updated)
+    }
+
+    async fn update_avatar(&self, uploads: &UploadSettings, user: &User, avatar: ProcessedAvatar) -> Result<User, AppError> {
+        //* A fresh name per upload so caches never serve the previous picture
+        let name = format!("avatars/{}-{}", user.id, Uuid::new_v4().simple());
+        let stored = async {
+            let url = storage::store(uploads, &format!("{}.jpg", name), &avatar.avatar).await?;
+            storage::store(uploads, &format!("{}_thumb.jpg", name), &avatar.thumbnail).await?;
+            Ok::<_, std::io::Error>(url)
+        }
+        .await;
+        let url = stored.map_err(|e| {
+            log::error!("Failed to store avatar: {:?}", e);
+            AppError::InternalServerError
+        })?;
+
+        let updated = sqlx::query_as::<_, User>("UPDATE users SET image = $1 WHERE id = $2 RETURNING *")
+            .bind(&url)
+            .bind(user.id)
+            .fetch_one(self.pool())
+            .await?;
+
+        if let Some(previous) = user.image.as_deref() {
+            remove_avatar(uploads, user.id, previous).await;
+        }
+        Ok(updated)
+    }
+
+    async fn export_data(&self, user: &User) -> Result<serde_json::Value, sqlx::Error> {
+        Ok(json!({
+            "exported_at": chrono::Utc::now(),
+            "profile": user,
+            "orders": self.export_rows("orders", user.id).await?,
+            "balances": self.export_rows("balances", user.id).await?,
+            "balance_histories": self.export_rows("balance_histories", user.id).await?,
+            "room_ratings": self.export_rows("room_ratings", user.id).await?,
+        }))
+    }
+
+    async fn delete_account(&self, uploads: &UploadSettings, user: &User, data: &DeleteAccountPayload) -> Result<(), AppError> {
+        self.check_password(user, &data.password).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        //* Orders and balances stay for bookkeeping, but nothing left on the row points to a person.
+        //* Usernames can't contain '@' and addresses need a dotted domain, so no sign-up can take these
+        sqlx::query(
+            r#"
+            UPDATE users SET
+                first_name = 'Deleted',
+                last_name = 'User',
+                username = 'deleted@' || id,
+                email = 'deleted@' || id,
+                password_hash = $2,
+                phone = '',
+                title = '',
+                image = NULL,
+                pending_email = NULL,
+                email_verified_at = NULL,
+                phone_verified_at = NULL,
+                deleted_at = CURRENT_TIMESTAMP
+            WHERE id = $1
+            "#,
+        )
+        .bind(user.id)
+        .bind(UNUSABLE_PASSWORD_HASH)
+        .execute(&mut *tx)
+        .await?;
+        //* Free text may name the guest
+        sqlx::query("UPDATE orders SET message = NULL WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE room_ratings SET review = NULL WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        //* Sessions keep IP addresses and user agents; dropping them also ends every login
+        for table in [
+            "sessions",
+            "otp_codes",
+            "user_two_factor",
+            "two_factor_recovery_codes",
+            "account_lockouts",
+            "account_lockout_events",
+            "user_identities",
+            "api_keys",
+        ] {
+            sqlx::query(&format!("DELETE FROM {} WHERE user_id = $1", table))
+                .bind(user.id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        //* The audit trail is append-only; its rows stay, minus what identifies the person
+        sqlx::query("SELECT anonymize_auth_events($1, $2, NULL)")
+            .bind(user.id)
+            .bind(vec![user.username.to_lowercase(), user.email.to_lowercase()])
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+
+        if let Some(image) = user.image.as_deref() {
+            remove_avatar(uploads, user.id, image).await;
+        }
+        Ok(())
+    }
+}
+
+/// Deletes an avatar and its thumbnail, but only files uploaded for this user; `image` can be
+/// set to any URL through the profile update, including someone else's avatar.
+async fn remove_avatar(uploads: &UploadSettings, user_id: i64, url: &str) {
+    let owned_prefix = format!("{}/avatars/{}-", uploads.public_path.trim_end_matches('/'), user_id);
+    if !url.starts_with(&owned_prefix) {
+        return;
+    }
+    storage::remove(uploads, url).await;
+    if let Some(thumbnail) = thumbnail_url(url) {
+        storage::remove(uploads, &thumbnail).await;
+    }
+}
+
+pub fn create_profile_service(pool: PostgresPool) -> Box<dyn ProfileService> {
+    Box::new(pool)
+}
@@ -0,0 +1,64 @@
+use async_trait::async_trait;
+
+use crate::{
+    domain::{errors::AppError, models::role::Role, services::RoleService},
+    infrastructure::database::PostgresPool,
+};
+
+#[async_trait]
+impl RoleService for PostgresPool {
+    async fn list(&self) -> Result<Vec<Role>, sqlx::Error> {
+        sqlx::query_as::<_, Role>("SELECT id, name, require_two_factor FROM roles ORDER BY id")
+            .fetch_all(self.pool())
+            .await
+    }
+
+    async fn find(&self, role_id: i32) -> Result<Option<Role>, sqlx::Error> {
+        sqlx::query_as::<_, Role>("SELECT id, name, require_two_factor FROM roles WHERE id = $1")
+            .bind(role_id)
+            .fetch_optional(self.pool())
+            .await
+    }
+
+    async fn create(&self, name: &str) -> Result<Role, sqlx::Error> {
+        sqlx::query_as::<_, Role>(
+            "INSERT INTO roles (name) VALUES ($1) RETURNING id, name, require_two_factor",
+        )
+        .bind(name)
+        .fetch_one(self.pool())
+        .await
+    }
+
+    async fn delete(&self, role_id: i32) -> Result<(), AppError> {
+        let assigned: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM users WHERE role_id = $1)")
+            .bind(role_id)
+            .fetch_one(self.pool())
+            .await?;
+        if assigned {
+            return Err(AppError::ValidationError("Role is still assigned to users".into()));
+        }
+
+        let result = sqlx::query("DELETE FROM roles WHERE id = $1")
+            .bind(role_id)
+            .execute(self.pool())
+            .await?;
+        if result.rows_affected() == 0 {
+            return Err(AppError::NotFound("Role".into()));
+        }
+        Ok(())
+    }
+
+    async fn set_two_factor_requirement(&self, role_id: i32, required: bool) -> Result<Option<Role>, sqlx::Error> {
+        sqlx::query_as::<_, Role>(
+            "UPDATE roles SET require_two_factor = $2 WHERE id = $1 RETURNING id, name, require_two_factor",
+        )
+        .bind(role_id)
+        .bind(required)
+        .fetch_optional(self.pool())
+        .await
+    }
+}
+
+pub fn create_role_service(pool: PostgresPool) -> Box<dyn RoleService> {
+    Box::new(pool)
+}
@@ -0,0 +1,109 @@
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        models::{
+            session::{ActiveSession, SessionMetadata},
+            token::AuthTokens,
+            user::User,
+        },
+        services::{token::issue_tokens, SessionService},
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::token_signing::TokenCodec,
+};
+
+/// Opens a new session for the user and issues its first token pair.
+pub async fn start_session(
+    conn: &mut PgConnection,
+    codec: &TokenCodec,
+    user: &User,
+    metadata: &SessionMetadata,
+) -> Result<AuthTokens, sqlx::Error> {
+    let session_id = Uuid::new_v4();
+    //* Expiry is pushed forward by issue_tokens on every rotation
+    sqlx::query(
+        "INSERT INTO sessions (id, user_id, user_agent, ip_address, expired_at) VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)",
+    )
+    .bind(session_id)
+    .bind(user.id)
+    .bind(&metadata.user_agent)
+    .bind(&metadata.ip_address)
+    .execute(&mut *conn)
+    .await?;
+
+    issue_tokens(conn, codec, user, session_id).await
+}
+
+/// Revokes every active session of the user.
+pub async fn revoke_all_sessions(conn: &mut PgConnection, user_id: i64) -> Result<u64, sqlx::Error> {
+    let result = sqlx::query(
+        "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND revoked_at IS NULL",
+    )
+    .bind(user_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[async_trait]
+impl SessionService for PostgresPool {
+    async fn touch(&self, session_id: Uuid, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE sessions SET last_seen_at = CURRENT_TIMESTAMP WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL AND expired_at > CURRENT_TIMESTAMP",
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn list_active(&self, user_id: i64, current: Uuid) -> Result<Vec<ActiveSession>, sqlx::Error> {
+        sqlx::query_as::<_, ActiveSession>(
+            r#"
+            SELECT id, user_agent, ip_address, created_at, last_seen_at, id = $2 AS is_current
+            FROM sessions
+            WHERE user_id = $1 AND revoked_at IS NULL AND expired_at > CURRENT_TIMESTAMP
+            ORDER BY last_seen_at DESC
+            "#,
+        )
+        .bind(user_id)
+        .bind(current)
+        .fetch_all(self.pool())
+        .await
+    }
+
+    async fn revoke(&self, user_id: i64, session_id: Uuid) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE id = $1 AND user_id = $2 AND revoked_at IS NULL",
+        )
+        .bind(session_id)
+        .bind(user_id)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn revoke_others(&self, user_id: i64, current: Uuid) -> Result<u64, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND id <> $2 AND revoked_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(current)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn revoke_all(&self, user_id: i64) -> Result<u64, sqlx::Error> {
+        let mut conn = self.pool().acquire().await?;
+        revoke_all_sessions(&mut conn, user_id).await
+    }
+}
+
+pub fn create_session_service(pool: PostgresPool) -> Box<dyn SessionService> {
+    Box::new(pool)
+}
@@ -1,34 +1,141 @@
 use crate::{
-    domain::models::token::Token, domain::services::TokenService,
+    domain::errors::AppError,
+    domain::models::session::{Session, SessionMetadata},
+    domain::models::token::{AuthTokens, RefreshToken},
+    domain::models::user::User,
+    domain::services::TokenService,
     infrastructure::database::PostgresPool,
+    shared::utils::{
+        generator::{generate_token, hash_token},
+        token_signing::TokenCodec,
+    },
 };
 use async_trait::async_trait;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+/// Signs a new access token for the session and stores a fresh refresh token in it.
+pub async fn issue_tokens(
+    conn: &mut PgConnection,
+    codec: &TokenCodec,
+    user: &User,
+    session_id: Uuid,
+) -> Result<AuthTokens, sqlx::Error> {
+    let access_token = codec
+        .sign(user, session_id)
+        .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
+    let refresh_token = generate_token(64);
+    sqlx::query(
+        "INSERT INTO refresh_tokens (user_id, session_id, token_hash, expired_at) VALUES ($1, $2, $3, CURRENT_TIMESTAMP + make_interval(days => $4))",
+    )
+    .bind(user.id)
+    .bind(session_id)
+    .bind(hash_token(&refresh_token))
+    .bind(codec.refresh_token_expiration_days())
+    .execute(&mut *conn)
+    .await?;
+
+    //* The session lives as long as its newest refresh token
+    sqlx::query(
+        "UPDATE sessions SET expired_at = CURRENT_TIMESTAMP + make_interval(days => $2) WHERE id = $1",
+    )
+    .bind(session_id)
+    .bind(codec.refresh_token_expiration_days())
+    .execute(&mut *conn)
+    .await?;
+
+    Ok(AuthTokens {
+        access_token,
+        refresh_token,
+        expires_in: codec.access_token_expires_in(),
+    })
+}
 
 #[async_trait]
 impl TokenService for PostgresPool {
-    async fn create(&self, token: &str) -> Result<Token, sqlx::Error> {
-        let token =
-            sqlx::query_as::<_, Token>("INSERT INTO revoked_token (token) VALUES ($1) RETURNING *")
-                .bind(token)
-                .fetch_one(self.pool())
+    async fn refresh(
+        &self,
+        codec: &TokenCodec,
+        refresh_token: &str,
+        metadata: &SessionMetadata,
+    ) -> Result<(User, AuthTokens), AppError> {
+        let mut tx = self.begin_transaction().await?;
+
+        let current = sqlx::query_as::<_, RefreshToken>(
+            "SELECT * FROM refresh_tokens WHERE token_hash = $1 AND expired_at > CURRENT_TIMESTAMP FOR UPDATE",
+        )
+        .bind(hash_token(refresh_token))
+        .fetch_optional(&mut *tx)
+        .await?;
+        let current = match current {
+            Some(current) => current,
+            None => return Err(AppError::Unauthorized),
+        };
+
+        //* A rotated token showing up again means it leaked; end the whole session
+        if current.is_used || current.is_revoked {
+            if current.is_used {
+                log::warn!(
+                    "Refresh token reuse detected for user {} (session {})",
+                    current.user_id,
+                    current.session_id
+                );
+            }
+            sqlx::query("UPDATE refresh_tokens SET is_revoked = true, updated_at = CURRENT_TIMESTAMP WHERE session_id = $1")
+                .bind(current.session_id)
+                .execute(&mut *tx)
                 .await?;
-        Ok(token)
-    }
-    async fn is_token_revoked(&self, token: &str) -> Result<bool, sqlx::Error> {
-        let token = sqlx::query_scalar::<_, bool>(
-            "SELECT EXISTS(SELECT 1 FROM revoked_token WHERE token = $1 AND is_revoked = true)",
+            sqlx::query("UPDATE sessions SET revoked_at = CURRENT_TIMESTAMP WHERE id = $1 AND revoked_at IS NULL")
+                .bind(current.session_id)
+                .execute(&mut *tx)
+                .await?;
+            tx.commit().await?;
+            return Err(AppError::Unauthorized);
+        }
+
+        let session = sqlx::query_as::<_, Session>(
+            "SELECT * FROM sessions WHERE id = $1 AND revoked_at IS NULL AND expired_at > CURRENT_TIMESTAMP",
         )
-        .bind(token)
-        .fetch_one(self.pool())
+        .bind(current.session_id)
+        .fetch_optional(&mut *tx)
         .await?;
-        Ok(token)
-    }
-    async fn revoke_token(&self, token: &str) -> Result<(), sqlx::Error> {
-        sqlx::query("UPDATE revoked_token SET is_revoked = true WHERE token = $1")
-            .bind(token)
-            .execute(self.pool())
+        if session.is_none() {
+            tx.rollback().await?;
+            return Err(AppError::Unauthorized);
+        }
+
+        sqlx::query("UPDATE refresh_tokens SET is_used = true, updated_at = CURRENT_TIMESTAMP WHERE id = $1")
+            .bind(current.id)
+            .execute(&mut *tx)
             .await?;
-        Ok(())
+
+        sqlx::query(
+            "UPDATE sessions SET last_seen_at = CURRENT_TIMESTAMP, user_agent = COALESCE($2, user_agent), ip_address = COALESCE($3, ip_address) WHERE id = $1",
+        )
+        .bind(current.session_id)
+        .bind(&metadata.user_agent)
+        .bind(&metadata.ip_address)
+        .execute(&mut *tx)
+        .await?;
+
+        let user = sqlx::query_as::<_, User>(
+            "SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(current.user_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let user = match user {
+            Some(user) => user,
+            None => {
+                tx.rollback().await?;
+                return Err(AppError::Unauthorized);
+            }
+        };
+
+        let tokens = issue_tokens(&mut tx, codec, &user, current.session_id).await?;
+        tx.commit().await?;
+        Ok((user, tokens))
     }
 }
 
@@ -0,0 +1,352 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use async_trait::async_trait;
+use sqlx::PgConnection;
+use totp_rs::{Algorithm, Secret, TOTP};
+
+use crate::{
+    config::SecuritySettings,
+    domain::{
+        errors::AppError,
+        models::{
+            otp::OtpPurpose,
+            session::SessionMetadata,
+            token::AuthTokens,
+            two_factor::{
+                TwoFactorChallenge, TwoFactorDisablePayload, TwoFactorLoginPayload, TwoFactorSetup,
+                UserTwoFactor,
+            },
+            user::User,
+        },
+        services::{
+            lockout::create_lockout_service,
+            otp::{consume_otp, create_otp_service},
+            session::start_session,
+            AuthService, TwoFactorService,
+        },
+    },
+    infrastructure::database::PostgresPool,
+    shared::utils::{
+        generator::{generate_token, hash_token},
+        password::verify_password,
+        token_signing::TokenCodec,
+    },
+};
+
+const TOTP_ISSUER: &str = "Karcis.com";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+    let secret = Secret::Encoded(secret.to_string()).to_bytes().map_err(|e| {
+        log::error!("Stored TOTP secret is not valid base32: {:?}", e);
+        AppError::InternalServerError
+    })?;
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        1,
+        TOTP_STEP,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        account_name.to_string(),
+    )
+    .map_err(|e| {
+        log::error!("Failed to build TOTP: {:?}", e);
+        AppError::InternalServerError
+    })
+}
+
+/// Returns the time step the code belongs to, allowing one step of clock drift either way.
+fn matching_step(totp: &TOTP, code: &str) -> Option<i64> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).ok()?.as_secs();
+    let current = now / TOTP_STEP;
+    [current, current.saturating_sub(1), current + 1]
+        .into_iter()
+        .find(|step| totp.generate(step * TOTP_STEP) == code)
+        .map(|step| step as i64)
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
+
+/// Replaces the user's recovery codes and returns the new ones in plain text.
+async fn replace_recovery_codes(conn: &mut PgConnection, user_id: i64) -> Result<Vec<String>, sqlx::Error> {
+    sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+        .bind(user_id)
+        .execute(&mut *conn)
+        .await?;
+
+    let mut codes = Vec::with_capacity(RECOVERY_CODE_COUNT);
+    for _ in 0..RECOVERY_CODE_COUNT {
+        let raw = generate_token(10).to_ascii_lowercase();
+        sqlx::query("INSERT INTO two_factor_recovery_codes (user_id, code_hash) VALUES ($1, $2)")
+            .bind(user_id)
+            .bind(hash_token(&raw))
+            .execute(&mut *conn)
+            .await?;
+        codes.push(format!("{}-{}", &raw[..5], &raw[5..]));
+    }
+    Ok(codes)
+}
+
+impl PostgresPool {
+    async fn find_two_factor(&self, user_id: i64) -> Result<Option<UserTwoFactor>, sqlx::Error> {
+        sqlx::query_as::<_, UserTwoFactor>("SELECT * FROM user_two_factor WHERE user_id = $1")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await
+    }
+
+    /// Checks an authenticator code and records its time step so it can't be replayed.
+    async fn verify_totp(&self, record: &UserTwoFactor, account_name: &str, code: &str) -> Result<bool, AppError> {
+        let totp = build_totp(&record.secret, account_name)?;
+        let step = match matching_step(&totp, code.trim()) {
+            Some(step) => step,
+            None => return Ok(false),
+        };
+        let result = sqlx::query(
+            "UPDATE user_two_factor SET last_used_step = $2, updated_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(record.user_id)
+        .bind(step)
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn use_recovery_code(&self, user_id: i64, code: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE two_factor_recovery_codes SET used_at = CURRENT_TIMESTAMP WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL",
+        )
+        .bind(user_id)
+        .bind(hash_token(&normalize_recovery_code(code)))
+        .execute(self.pool())
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Accepts either an authenticator code or an unused recovery code.
+    async fn verify_second_factor(&self, record: &UserTwoFactor, account_name: &str, code: &str) -> Result<bool, AppError> {
+        let code = code.trim();
+        if code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit()) {
+            return self.verify_totp(record, account_name, code).await;
+        }
+        Ok(self.use_recovery_code(record.user_id, code).await?)
+    }
+
+    async fn enabled_two_factor(&self, user_id: i64) -> Result<UserTwoFactor, AppError> {
+        match self.find_two_factor(user_id).await? {
+            Some(record) if record.enabled_at.is_some() => Ok(record),
+            _ => Err(AppError::ValidationError("Two-factor authentication is not enabled".into())),
+        }
+    }
+}
+
+#[async_trait]
+impl TwoFactorService for PostgresPool {
+    async fn is_enabled(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        Ok(self
+            .find_two_factor(user_id)
+            .await?
+            .is_some_and(|record| record.enabled_at.is_some()))
+    }
+
+    async fn enrollment_required(&self, user: &User) -> Result<bool, sqlx::Error> {
+        let required = sqlx::query_scalar::<_, bool>(
+            r#"
+            SELECT r.require_two_factor AND NOT EXISTS (
+                SELECT 1 FROM user_two_factor t WHERE t.user_id = $1 AND t.enabled_at IS NOT NULL
+            )
+            FROM roles r
+            WHERE r.id = $2
+            "#,
+        )
+        .bind(user.id)
+        .bind(user.role_id)
+        .fetch_optional(self.pool())
+        .await?;
+        Ok(required.unwrap_or(false))
+    }
+
+    async fn setup(&self, user: &User) -> Result<TwoFactorSetup, AppError> {
+        if self.is_enabled(user.id).await? {
+            return Err(AppError::Conflict("Two-factor authentication".into()));
+        }
+
+        let secret = Secret::generate_secret().to_encoded().to_string();
+        let totp = build_totp(&secret, &user.email)?;
+
+        //* Starting over replaces any secret that was never confirmed
+        sqlx::query(
+            r#"
+            INSERT INTO user_two_factor (user_id, secret) VALUES ($1, $2)
+            ON CONFLICT (user_id) DO UPDATE
+            SET secret = EXCLUDED.secret, last_used_step = NULL, updated_at = CURRENT_TIMESTAMP
+            "#,
+        )
+        .bind(user.id)
+        .bind(&secret)
+        .execute(self.pool())
+        .await?;
+
+        Ok(TwoFactorSetup {
+            secret,
+            otpauth_uri: totp.get_url(),
+        })
+    }
+
+    async fn confirm(&self, user: &User, code: &str) -> Result<Vec<String>, AppError> {
+        let record = match self.find_two_factor(user.id).await? {
+            Some(record) if record.enabled_at.is_some() => {
+                return Err(AppError::Conflict("Two-factor authentication".into()))
+            }
+            Some(record) => record,
+            None => {
+                return Err(AppError::ValidationError(
+                    "Two-factor authentication setup has not been started".into(),
+                ))
+            }
+        };
+
+        if !self.verify_totp(&record, &user.email, code).await? {
+            return Err(AppError::ValidationError("Invalid two-factor code".into()));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        sqlx::query("UPDATE user_two_factor SET enabled_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        let codes = replace_recovery_codes(&mut tx, user.id).await?;
+        tx.commit().await?;
+        Ok(codes)
+    }
+
+    async fn disable(&self, user: &User, data: &TwoFactorDisablePayload) -> Result<(), AppError> {
+        let password_matches = verify_password(&data.password, &user.password_hash)
+            .await
+            .map_err(|e| {
+                log::error!("Failed to verify password: {:?}", e);
+                AppError::InternalServerError
+            })?;
+        if !password_matches {
+            return Err(AppError::InvalidCredentials);
+        }
+        let record = self.enabled_two_factor(user.id).await?;
+
+        let required: bool = sqlx::query_scalar("SELECT require_two_factor FROM roles WHERE id = $1")
+            .bind(user.role_id)
+            .fetch_optional(self.pool())
+            .await?
+            .unwrap_or(false);
+        if required {
+            return Err(AppError::Forbidden);
+        }
+
+        if !self.verify_second_factor(&record, &user.email, &data.code).await? {
+            return Err(AppError::ValidationError("Invalid two-factor code".into()));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        sqlx::query("DELETE FROM two_factor_recovery_codes WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("DELETE FROM user_two_factor WHERE user_id = $1")
+            .bind(user.id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn regenerate_recovery_codes(&self, user: &User, code: &str) -> Result<Vec<String>, AppError> {
+        let record = self.enabled_two_factor(user.id).await?;
+        if !self.verify_totp(&record, &user.email, code).await? {
+            return Err(AppError::ValidationError("Invalid two-factor code".into()));
+        }
+
+        let mut tx = self.begin_transaction().await?;
+        let codes = replace_recovery_codes(&mut tx, user.id).await?;
+        tx.commit().await?;
+        Ok(codes)
+    }
+
+    async fn create_challenge(&self, user_id: i64) -> Result<TwoFactorChallenge, sqlx::Error> {
+        let challenge_token = generate_token(64);
+        let otp_service = create_otp_service(self.clone());
+        otp_service
+            .create(user_id, OtpPurpose::TwoFactorLogin, &hash_token(&challenge_token))
+            .await?;
+        Ok(TwoFactorChallenge {
+            challenge_token,
+            expires_in: OtpPurpose::TwoFactorLogin.expiration_minutes() as i64 * 60,
+        })
+    }
+
+    async fn complete_login(
+        &self,
+        codec: &TokenCodec,
+        security: &SecuritySettings,
+        data: &TwoFactorLoginPayload,
+        metadata: &SessionMetadata,
+    ) -> Result<(User, AuthTokens), AppError> {
+        let otp_service = create_otp_service(self.clone());
+        let challenge = otp_service
+            .find_active_by_code(OtpPurpose::TwoFactorLogin, &hash_token(&data.challenge_token))
+            .await?;
+        let challenge = match challenge {
+            Some(challenge) => challenge,
+            None => return Err(AppError::Unauthorized),
+        };
+        //* Every code tried counts against the challenge, right or wrong
+        if !otp_service.take_attempt(challenge.id).await? {
+            return Err(AppError::Unauthorized);
+        }
+        let user_id = challenge.user_id.ok_or(AppError::Unauthorized)?;
+
+        let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1 AND deleted_at IS NULL")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or(AppError::Unauthorized)?;
+        let record = self
+            .enabled_two_factor(user.id)
+            .await
+            .map_err(|_| AppError::Unauthorized)?;
+
+        let lockout_service = create_lockout_service(self.clone());
+        lockout_service.ensure_unlocked(user.id).await?;
+        if !self.verify_second_factor(&record, &user.email, &data.code).await? {
+            if let Some(duration) = lockout_service.record_failure(security, user.id, metadata).await? {
+                return Err(AppError::TooManyRequests(duration));
+            }
+            return Err(AppError::InvalidCredentials);
+        }
+        lockout_service.reset(user.id).await?;
+
+        let mut tx = self.begin_transaction().await?;
+        //* The challenge is single use; a concurrent attempt that got here first wins
+        if !consume_otp(&mut tx, challenge.id).await? {
+            tx.rollback().await?;
+            return Err(AppError::Unauthorized);
+        }
+        let tokens = start_session(&mut tx, codec, &user, metadata).await?;
+        tx.commit().await?;
+        //* Sign-ins held back for a second factor get their alert once it passes
+        if let Err(e) = AuthService::send_login_alert(self, security, &user, metadata).await {
+            log::error!("Failed to send login alert for user {}: {:?}", user.id, e);
+        }
+        Ok((user, tokens))
+    }
+}
+
+pub fn create_two_factor_service(pool: PostgresPool) -> Box<dyn TwoFactorService> {
+    Box::new(pool)
+}
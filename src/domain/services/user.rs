@@ -1,19 +1,25 @@
 use async_trait::async_trait;
-use bcrypt::{hash, verify};
-use chrono::{Duration, Utc};
 
 use crate::{
+    config::{PasswordSettings, SecuritySettings},
     domain::{
-        models::{auth::{LoginPayload, RegisterPayload}, user::User, token::TokenSigning},
-        services::UserService,
+        errors::AppError,
+        models::{auth::{LoginOutcome, LoginPayload, RegisterPayload}, session::SessionMetadata, user::{User, UserQuery}, token::AuthTokens},
+        services::{lockout::create_lockout_service, session::{revoke_all_sessions, start_session}, two_factor::create_two_factor_service, AuthService, UserService},
     },
     infrastructure::database::PostgresPool,
+    shared::utils::{
+        password::{hash_password, needs_rehash, verify_password},
+        phone::normalize_phone,
+        token_signing::TokenCodec,
+    },
 };
 
 #[async_trait]
 impl UserService for PostgresPool {
     async fn find_by(&self, field: &str, value: &str) -> Result<Option<User>, sqlx::Error> {
-        let query = format!("SELECT * FROM users WHERE {} = $1", field);
+        //* Usernames and emails are unique regardless of case
+        let query = format!("SELECT * FROM users WHERE LOWER({}) = LOWER($1)", field);
         let result = sqlx::query_as::<_, User>(&query)
             .bind(value)
             .fetch_optional(self.pool())
@@ -21,20 +27,24 @@ impl UserService for PostgresPool {
         result
     }
 
-    async fn create(&self, user: &RegisterPayload) -> Result<(User, String), sqlx::Error> {
+    async fn create(&self, codec: &TokenCodec, passwords: &PasswordSettings, user: &RegisterPayload, metadata: &SessionMetadata) -> Result<(User, AuthTokens), sqlx::Error> {
+        //* Hash before opening the transaction so no connection waits on it
+        let password_hash = hash_password(&user.password, passwords)
+            .await
+            .map_err(|e| sqlx::Error::Encode(Box::new(e)))?;
+
         //* Begin transaction
         let mut tx = self.begin_transaction().await?;
         
         //* Execute operations within transaction
         let result = async{
             //* Create user
-            let password_hash = hash(&user.password, 10).unwrap();
             let created_user = sqlx::query_as::<_, User>(
                 "INSERT INTO users (first_name, last_name, phone, username, email, password_hash, title, image) VALUES ($1, $2, $3, $4, $5, $6, $7, $8) RETURNING *",
             )
             .bind(&user.first_name)
             .bind(&user.last_name)
-            .bind(&user.phone)
+            .bind(normalize_phone(&user.phone).unwrap_or_else(|| user.phone.clone()))
             .bind(&user.username)
             .bind(&user.email)
             .bind(&password_hash)
@@ -49,22 +59,10 @@ impl UserService for PostgresPool {
                 .execute(&mut *tx)
                 .await;
 
-            //* Create token
-            let token_data = TokenSigning {
-                id: created_user.id.clone(),
-                first_name: created_user.first_name.clone(),
-                last_name: created_user.last_name.clone(),
-                email: created_user.email.clone(),
-                role_id: created_user.role_id.clone(),
-                exp: (Utc::now() + Duration::hours(24)).timestamp() as usize,
-            };
-            let token = TokenSigning::sign(token_data).unwrap();
-            sqlx::query("INSERT INTO revoked_token (token) VALUES ($1)")
-                .bind(&token)
-                .execute(&mut *tx)
-                .await?;
+            //* Create session
+            let tokens = start_session(&mut tx, codec, &created_user, metadata).await?;
 
-            Ok((created_user, token))
+            Ok((created_user, tokens))
         }.await;
 
         //* Handle result and commit/rollback transaction
@@ -80,30 +78,149 @@ impl UserService for PostgresPool {
         }
     }
 
-    async fn login(&self, data: &LoginPayload) -> Result<(User, String), sqlx::Error> {
-        let user = self.find_by("username", &data.username).await?;
+    async fn login(&self, codec: &TokenCodec, security: &SecuritySettings, passwords: &PasswordSettings, data: &LoginPayload, metadata: &SessionMetadata) -> Result<LoginOutcome, AppError> {
+        let field = if data.identifier.contains('@') { "email" } else { "username" };
+        //* Soft-deleted accounts can't sign in
+        let user = self
+            .find_by(field, data.identifier.trim())
+            .await?
+            .filter(|user| user.deleted_at.is_none());
         if let Some(user) = user {
-            if !verify(&data.password, &user.password_hash).unwrap() {
-                return Err(sqlx::Error::RowNotFound);
+            //* A locked account doesn't get to try passwords
+            let lockout_service = create_lockout_service(self.clone());
+            lockout_service.ensure_unlocked(user.id).await?;
+            let password_matches = verify_password(&data.password, &user.password_hash)
+                .await
+                .map_err(|e| {
+                    log::error!("Failed to verify password: {:?}", e);
+                    AppError::InternalServerError
+                })?;
+            if !password_matches {
+                if let Some(duration) = lockout_service.record_failure(security, user.id, metadata).await? {
+                    return Err(AppError::TooManyRequests(duration));
+                }
+                return Err(AppError::InvalidCredentials);
+            }
+            //* The password is known to be right here, so upgrade a stale hash
+            if needs_rehash(&user.password_hash, passwords) {
+                match hash_password(&data.password, passwords).await {
+                    Ok(password_hash) => {
+                        sqlx::query("UPDATE users SET password_hash = $1 WHERE id = $2")
+                            .bind(&password_hash)
+                            .bind(user.id)
+                            .execute(self.pool())
+                            .await?;
+                    }
+                    Err(e) => log::error!("Failed to rehash password for user {}: {:?}", user.id, e),
+                }
             }
-            //* Create token
-            let token_data = TokenSigning {
-                id: user.id.clone(),
-                first_name: user.first_name.clone(),
-                last_name: user.last_name.clone(),
-                email: user.email.clone(),
-                role_id: user.role_id.clone(),
-                exp: (Utc::now() + Duration::hours(24)).timestamp() as usize,
-            };
-            let token = TokenSigning::sign(token_data).unwrap();
-            sqlx::query("INSERT INTO revoked_token (token) VALUES ($1)")
-                .bind(&token)
-                .execute(self.pool())
-                .await?;
-            Ok((user, token))
+            //* Hold the session back until the second factor is checked
+            let two_factor_service = create_two_factor_service(self.clone());
+            if two_factor_service.is_enabled(user.id).await? {
+                let challenge = two_factor_service.create_challenge(user.id).await?;
+                return Ok(LoginOutcome::TwoFactorRequired(challenge));
+            }
+            lockout_service.reset(user.id).await?;
+            //* Create session
+            let mut tx = self.begin_transaction().await?;
+            let tokens = start_session(&mut tx, codec, &user, metadata).await?;
+            tx.commit().await?;
+            //* A missed alert shouldn't cost the user their sign-in
+            if let Err(e) = AuthService::send_login_alert(self, security, &user, metadata).await {
+                log::error!("Failed to send login alert for user {}: {:?}", user.id, e);
+            }
+            Ok(LoginOutcome::Authenticated(Box::new(user), tokens))
         } else {
-            Err(sqlx::Error::RowNotFound)
+            Err(AppError::InvalidCredentials)
+        }
+    }
+
+    async fn find(&self, user_id: i64) -> Result<Option<User>, sqlx::Error> {
+        sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await
+    }
+
+    async fn list(&self, query: &UserQuery, limit: i64, offset: i64) -> Result<(Vec<User>, i64), sqlx::Error> {
+        //* Escape LIKE wildcards so the search term is matched literally
+        let pattern = query
+            .search
+            .as_deref()
+            .map(str::trim)
+            .filter(|search| !search.is_empty())
+            .map(|search| {
+                let escaped = search.replace('\\', "\\\\").replace('%', "\\%").replace('_', "\\_");
+                format!("%{}%", escaped)
+            });
+        let filter = r#"
+            WHERE ($1::TEXT IS NULL
+                OR username ILIKE $1 OR email ILIKE $1
+                OR first_name ILIKE $1 OR last_name ILIKE $1)
+            AND ($2::INT IS NULL OR role_id = $2)
+            AND ($3 OR deleted_at IS NULL)
+        "#;
+
+        let total = sqlx::query_scalar::<_, i64>(&format!("SELECT COUNT(*) FROM users {}", filter))
+            .bind(&pattern)
+            .bind(query.role_id)
+            .bind(query.include_deleted)
+            .fetch_one(self.pool())
+            .await?;
+        let users = sqlx::query_as::<_, User>(&format!(
+            "SELECT * FROM users {} ORDER BY id LIMIT $4 OFFSET $5",
+            filter
+        ))
+        .bind(&pattern)
+        .bind(query.role_id)
+        .bind(query.include_deleted)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(self.pool())
+        .await?;
+        Ok((users, total))
+    }
+
+    async fn set_role(&self, user_id: i64, role_id: i32) -> Result<User, AppError> {
+        let role_exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM roles WHERE id = $1)")
+            .bind(role_id)
+            .fetch_one(self.pool())
+            .await?;
+        if !role_exists {
+            return Err(AppError::NotFound("Role".into()));
         }
+
+        sqlx::query_as::<_, User>("UPDATE users SET role_id = $1 WHERE id = $2 RETURNING *")
+            .bind(role_id)
+            .bind(user_id)
+            .fetch_optional(self.pool())
+            .await?
+            .ok_or_else(|| AppError::NotFound("User".into()))
+    }
+
+    async fn soft_delete(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.begin_transaction().await?;
+        let result = sqlx::query(
+            "UPDATE users SET deleted_at = CURRENT_TIMESTAMP WHERE id = $1 AND deleted_at IS NULL",
+        )
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            tx.rollback().await?;
+            return Ok(false);
+        }
+        revoke_all_sessions(&mut tx, user_id).await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn restore(&self, user_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE users SET deleted_at = NULL WHERE id = $1 AND deleted_at IS NOT NULL")
+            .bind(user_id)
+            .execute(self.pool())
+            .await?;
+        Ok(result.rows_affected() > 0)
     }
 }
 
@@ -0,0 +1,42 @@
+use crate::domain::models::api_key::CreateApiKeyPayload;
+use crate::domain::validations::auth_validations::ValidationError;
+
+pub const MAX_API_KEY_HOTELS: usize = 50;
+pub const MAX_API_KEY_LIFETIME_DAYS: i32 = 365;
+
+pub struct ApiKeyValidator;
+
+impl ApiKeyValidator {
+    pub fn validate_create_api_key_payload(
+        payload: &CreateApiKeyPayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.name.trim().is_empty() {
+            errors.push("Name is required".into());
+        } else if payload.name.len() > 100 {
+            errors.push("Name must be at most 100 characters".into());
+        }
+        if payload.scopes.is_empty() {
+            errors.push("At least one scope is required".into());
+        }
+        if payload.hotel_ids.len() > MAX_API_KEY_HOTELS {
+            errors.push(format!("A key can be restricted to at most {} hotels", MAX_API_KEY_HOTELS));
+        }
+        if payload
+            .expires_in_days
+            .is_some_and(|days| !(1..=MAX_API_KEY_LIFETIME_DAYS).contains(&days))
+        {
+            errors.push(format!(
+                "Expiry must be between 1 and {} days",
+                MAX_API_KEY_LIFETIME_DAYS
+            ));
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,17 @@
-use crate::domain::models::auth::{LoginPayload, RegisterPayload};
+use crate::config::PasswordSettings;
+use crate::domain::models::auth::{
+    LoginPayload, OtpCheckPayload, RegisterPayload, ResetPasswordPayload, VerifyEmailPayload,
+};
+use crate::domain::validations::password_validations::PasswordValidator;
+use crate::shared::utils::phone::normalize_phone;
 
 pub struct AuthValidator;
 
 impl AuthValidator {
-    pub fn validate_register_payload(payload: &RegisterPayload) -> Result<(), ValidationError> {
+    pub fn validate_register_payload(
+        payload: &RegisterPayload,
+        password_settings: &PasswordSettings,
+    ) -> Result<(), ValidationError> {
         let mut errors = Vec::new();
 
         // Validation logic here
@@ -15,6 +23,9 @@ impl AuthValidator {
         }
         if payload.username.is_empty() {
             errors.push("Username is required".into());
+        } else if payload.username.contains('@') {
+            //* '@' tells an email apart from a username at login
+            errors.push("Username must not contain '@'".into());
         }
         if payload.email.is_empty() {
             errors.push("Email is required".into());
@@ -25,20 +36,26 @@ impl AuthValidator {
         }
         if payload.password.is_empty() {
             errors.push("Password is required".into());
+        } else if let Err(ValidationError::Multiple(password_errors)) = PasswordValidator::validate(
+            password_settings,
+            &payload.password,
+            &payload.username,
+            &payload.email,
+        ) {
+            errors.extend(password_errors);
         }
         if payload.phone.is_empty() {
             errors.push("Phone number is required".into());
         } else {
-            let phone_regex = regex::Regex::new(r"^\+?[1-9]\d{1,14}$").unwrap(); // Example regex for international phone numbers
-            if !phone_regex.is_match(&payload.phone) {
+            if normalize_phone(&payload.phone).is_none() {
                 errors.push("Invalid phone number format".into());
             }
         }
         if payload.title.is_empty() {
             errors.push("Title is required".into());
         }
-        if payload.image.is_empty() {
-            errors.push("Image is required".into());
+        if payload.image.as_ref().is_some_and(|image| image.trim().is_empty()) {
+            errors.push("Image must not be empty".into());
         }
 
         if !errors.is_empty() {
@@ -51,12 +68,73 @@ impl AuthValidator {
     pub fn validate_login_payload(payload: &LoginPayload) -> Result<(), ValidationError> {
         let mut errors = Vec::new();
 
-        if payload.username.is_empty() {
-            errors.push("Username is required".into());
+        if payload.identifier.is_empty() {
+            errors.push("Username or email is required".into());
+        }
+        if payload.password.is_empty() {
+            errors.push("Password is required".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_otp_check_payload(payload: &OtpCheckPayload) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.email.is_empty() {
+            errors.push("Email is required".into());
+        }
+        if payload.otp.is_empty() {
+            errors.push("OTP is required".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_verify_email_payload(
+        payload: &VerifyEmailPayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.email.is_empty() {
+            errors.push("Email is required".into());
+        }
+        if payload.otp.is_empty() {
+            errors.push("OTP is required".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_reset_password_payload(
+        payload: &ResetPasswordPayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.email.is_empty() {
+            errors.push("Email is required".into());
+        }
+        if payload.otp.is_empty() {
+            errors.push("OTP is required".into());
         }
         if payload.password.is_empty() {
             errors.push("Password is required".into());
         }
+        if payload.password != payload.password_confirmation {
+            errors.push("Password confirmation does not match".into());
+        }
 
         if !errors.is_empty() {
             return Err(ValidationError::Multiple(errors));
@@ -1 +1,5 @@
-pub mod auth_validations;
\ No newline at end of file
+pub mod api_key_validations;
+pub mod auth_validations;
+pub mod password_validations;
+pub mod profile_validations;
+pub mod role_validations;
\ No newline at end of file
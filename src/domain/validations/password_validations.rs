@@ -0,0 +1,147 @@
+use std::{collections::HashSet, sync::LazyLock};
+
+use crate::config::PasswordSettings;
+use crate::domain::validations::auth_validations::ValidationError;
+
+/// Offline list of common and breached passwords, one lowercase entry per line.
+static COMMON_PASSWORDS: LazyLock<HashSet<&'static str>> =
+    LazyLock::new(|| include_str!("common_passwords.txt").lines().collect());
+
+/// Parts of the username or email shorter than this are not checked.
+const MIN_PERSONAL_INFO_LENGTH: usize = 3;
+
+pub struct PasswordValidator;
+
+impl PasswordValidator {
+    pub fn validate(
+        settings: &PasswordSettings,
+        password: &str,
+        username: &str,
+        email: &str,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+        let length = password.chars().count();
+
+        if length < settings.min_length {
+            errors.push(format!("Password must be at least {} characters long", settings.min_length));
+        }
+        if password.len() > settings.max_length {
+            errors.push(format!("Password must be at most {} bytes long", settings.max_length));
+        }
+        if settings.require_lowercase && !password.chars().any(|c| c.is_lowercase()) {
+            errors.push("Password must contain a lowercase letter".into());
+        }
+        if settings.require_uppercase && !password.chars().any(|c| c.is_uppercase()) {
+            errors.push("Password must contain an uppercase letter".into());
+        }
+        if settings.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            errors.push("Password must contain a digit".into());
+        }
+        if settings.require_symbol && password.chars().all(|c| c.is_alphanumeric()) {
+            errors.push("Password must contain a symbol".into());
+        }
+
+        let lowered = password.to_lowercase();
+        if settings.reject_personal_info {
+            let email = email.to_lowercase();
+            let local_part = email.split('@').next().unwrap_or_default();
+            let personal = [username.to_lowercase(), local_part.to_string()];
+            if personal
+                .iter()
+                .any(|part| part.chars().count() >= MIN_PERSONAL_INFO_LENGTH && lowered.contains(part.as_str()))
+            {
+                errors.push("Password must not contain your username or email".into());
+            }
+        }
+        if settings.reject_common && COMMON_PASSWORDS.contains(lowered.as_str()) {
+            errors.push("Password is too common".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn errors(settings: &PasswordSettings, password: &str) -> Vec<String> {
+        match PasswordValidator::validate(settings, password, "budi", "budi.santoso@example.com") {
+            Ok(()) => vec![],
+            Err(ValidationError::Multiple(errors)) => errors,
+            Err(ValidationError::Single(error)) => vec![error],
+        }
+    }
+
+    #[test]
+    fn accepts_a_password_meeting_the_default_policy() {
+        assert!(errors(&PasswordSettings::default(), "Kopi-Tubruk7").is_empty());
+    }
+
+    #[test]
+    fn reports_every_missing_character_class() {
+        let errors = errors(&PasswordSettings::default(), "!!!!!!!!");
+        assert!(errors.contains(&"Password must contain a lowercase letter".to_string()));
+        assert!(errors.contains(&"Password must contain an uppercase letter".to_string()));
+        assert!(errors.contains(&"Password must contain a digit".to_string()));
+    }
+
+    #[test]
+    fn requires_a_symbol_only_when_configured() {
+        let settings = PasswordSettings {
+            require_symbol: true,
+            ..Default::default()
+        };
+        assert_eq!(errors(&settings, "KopiTubruk7"), vec!["Password must contain a symbol"]);
+        assert!(errors(&PasswordSettings::default(), "KopiTubruk7").is_empty());
+    }
+
+    #[test]
+    fn counts_length_in_characters_and_caps_it_in_bytes() {
+        let settings = PasswordSettings::default();
+        assert_eq!(errors(&settings, "Kopi7"), vec!["Password must be at least 8 characters long"]);
+        //* 8 characters but 16 bytes: long enough, and within the 72 byte bcrypt limit
+        assert!(errors(&settings, "Kopi7éééé").is_empty());
+        let too_long = format!("Kopi7{}", "é".repeat(34));
+        assert_eq!(errors(&settings, &too_long), vec!["Password must be at most 72 bytes long"]);
+    }
+
+    #[test]
+    fn rejects_the_username_or_email_local_part() {
+        let settings = PasswordSettings::default();
+        let personal = vec!["Password must not contain your username or email"];
+        assert_eq!(errors(&settings, "xBUDIx2024"), personal);
+        assert_eq!(errors(&settings, "Budi.Santoso9"), personal);
+    }
+
+    #[test]
+    fn ignores_personal_info_too_short_to_matter() {
+        let result = PasswordValidator::validate(&PasswordSettings::default(), "KopiTubruk7", "ko", "ko@example.com");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn rejects_common_passwords_case_insensitively() {
+        let settings = PasswordSettings::default();
+        assert_eq!(errors(&settings, "Password1"), vec!["Password is too common"]);
+        assert_eq!(errors(&settings, "QWERTY123a"), Vec::<String>::new());
+        let lenient = PasswordSettings {
+            reject_common: false,
+            ..Default::default()
+        };
+        assert!(errors(&lenient, "Password1").is_empty());
+    }
+
+    #[test]
+    fn common_password_list_is_lowercase_without_blank_lines() {
+        assert!(!COMMON_PASSWORDS.is_empty());
+        assert!(COMMON_PASSWORDS
+            .iter()
+            .all(|password| !password.is_empty() && *password == password.to_lowercase()));
+        assert!(COMMON_PASSWORDS.contains("qwerty123"));
+    }
+}
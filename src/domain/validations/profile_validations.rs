@@ -0,0 +1,141 @@
+use crate::config::PasswordSettings;
+use crate::domain::models::{
+    profile::{ChangeEmailPayload, ChangePasswordPayload, SetPasswordPayload, UpdateProfilePayload},
+    user::User,
+};
+use crate::domain::validations::{
+    auth_validations::ValidationError, password_validations::PasswordValidator,
+};
+use crate::shared::utils::phone::normalize_phone;
+
+pub struct ProfileValidator;
+
+impl ProfileValidator {
+    pub fn validate_update_profile_payload(
+        payload: &UpdateProfilePayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        //* Only fields that are present are checked; they may not be blanked
+        if payload.first_name.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            errors.push("First Name must not be empty".into());
+        }
+        if payload.last_name.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            errors.push("Last Name must not be empty".into());
+        }
+        if payload.title.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            errors.push("Title must not be empty".into());
+        }
+        if payload.image.as_ref().is_some_and(|v| v.trim().is_empty()) {
+            errors.push("Image must not be empty".into());
+        }
+        if let Some(phone) = &payload.phone {
+            if normalize_phone(phone).is_none() {
+                errors.push("Invalid phone number format".into());
+            }
+        }
+        for value in [
+            &payload.first_name,
+            &payload.last_name,
+            &payload.title,
+            &payload.image,
+        ]
+        .into_iter()
+        .flatten()
+        {
+            if value.len() > 255 {
+                errors.push("Profile fields must be at most 255 characters".into());
+                break;
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_set_password_payload(
+        payload: &SetPasswordPayload,
+        password_settings: &PasswordSettings,
+        user: &User,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.password.is_empty() {
+            errors.push("Password is required".into());
+        } else if let Err(ValidationError::Multiple(password_errors)) = PasswordValidator::validate(
+            password_settings,
+            &payload.password,
+            &user.username,
+            &user.email,
+        ) {
+            errors.extend(password_errors);
+        }
+        if payload.password != payload.password_confirmation {
+            errors.push("Password confirmation does not match".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_change_password_payload(
+        payload: &ChangePasswordPayload,
+        password_settings: &PasswordSettings,
+        user: &User,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.current_password.is_empty() {
+            errors.push("Current password is required".into());
+        }
+        if payload.password.is_empty() {
+            errors.push("Password is required".into());
+        } else if let Err(ValidationError::Multiple(password_errors)) = PasswordValidator::validate(
+            password_settings,
+            &payload.password,
+            &user.username,
+            &user.email,
+        ) {
+            errors.extend(password_errors);
+        }
+        if payload.password != payload.password_confirmation {
+            errors.push("Password confirmation does not match".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_change_email_payload(
+        payload: &ChangeEmailPayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        if payload.email.is_empty() {
+            errors.push("Email is required".into());
+        } else {
+            let email_regex = regex::Regex::new(r"^[\w\.-]+@[\w\.-]+\.\w+$").unwrap();
+            if !email_regex.is_match(&payload.email) {
+                errors.push("Invalid email format".into());
+            }
+        }
+        if payload.password.is_empty() {
+            errors.push("Password is required".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,38 @@
+use crate::domain::models::{permission::CreatePermissionPayload, role::CreateRolePayload};
+use crate::domain::validations::auth_validations::ValidationError;
+
+pub struct RoleValidator;
+
+impl RoleValidator {
+    pub fn validate_create_role_payload(payload: &CreateRolePayload) -> Result<(), ValidationError> {
+        let name_regex = regex::Regex::new(r"^[a-z][a-z0-9_]{1,49}$").unwrap();
+        if !name_regex.is_match(&payload.name) {
+            return Err(ValidationError::Single(
+                "Role name must be 2-50 lowercase letters, digits or underscores".into(),
+            ));
+        }
+
+        Ok(())
+    }
+
+    pub fn validate_create_permission_payload(
+        payload: &CreatePermissionPayload,
+    ) -> Result<(), ValidationError> {
+        let mut errors = Vec::new();
+
+        //* Permissions are named `resource:action`, e.g. `hotel:write`
+        let name_regex = regex::Regex::new(r"^[a-z][a-z0-9_]*:[a-z][a-z0-9_]*$").unwrap();
+        if !name_regex.is_match(&payload.name) || payload.name.len() > 100 {
+            errors.push("Permission name must look like 'resource:action'".into());
+        }
+        if payload.description.as_ref().is_some_and(|d| d.len() > 255) {
+            errors.push("Description must be at most 255 characters".into());
+        }
+
+        if !errors.is_empty() {
+            return Err(ValidationError::Multiple(errors));
+        }
+
+        Ok(())
+    }
+}
@@ -10,6 +10,25 @@ pub async fn send_mail(
     data: User,
     subject: &str,
     template: String,
+) -> Result<<SmtpTransport as Transport>::Ok, <SmtpTransport as Transport>::Error> {
+    deliver(data, subject, template)
+}
+
+/// Sends on the blocking pool without waiting for the SMTP server, for mail the request
+/// doesn't depend on; a failure is only logged.
+pub fn spawn_mail(data: User, subject: &str, template: String) {
+    let subject = subject.to_string();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = deliver(data, &subject, template) {
+            log::error!("Failed to send \"{}\" email: {:?}", subject, e);
+        }
+    });
+}
+
+fn deliver(
+    data: User,
+    subject: &str,
+    template: String,
 ) -> Result<<SmtpTransport as Transport>::Ok, <SmtpTransport as Transport>::Error> {
     let settings = config::Settings::load().expect("Failed to load configuration");
     let host = settings.email.smtp_host;
@@ -26,7 +45,7 @@ pub async fn send_mail(
     let mailer = SmtpTransport::starttls_relay(host.as_str())?
         .credentials(creds)
         .authentication(vec![Mechanism::Plain, Mechanism::Login])
-        .build()?;
+        .build();
 
     let recipient = format!(
         "{} {} {} <{}>",
@@ -1 +1,4 @@
-pub mod forgot_password;
\ No newline at end of file
+pub mod email_verification;
+pub mod forgot_password;
+pub mod login_alert;
+pub mod magic_link;
\ No newline at end of file
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+use crate::{
+    config::MaintenanceSettings,
+    domain::services::maintenance::create_maintenance_service,
+    infrastructure::database::PostgresPool,
+};
+
+/// Runs the purge every `interval_seconds`, starting right away, for as long as the server runs.
+pub fn spawn_purge_task(pool: PostgresPool, settings: MaintenanceSettings) {
+    if !settings.enabled {
+        log::info!("Maintenance purge task is disabled");
+        return;
+    }
+    actix_web::rt::spawn(async move {
+        let maintenance_service = create_maintenance_service(pool);
+        let mut interval = actix_web::rt::time::interval(Duration::from_secs(settings.interval_seconds.max(1)));
+        loop {
+            interval.tick().await;
+            match maintenance_service.purge_expired(&settings).await {
+                Ok(report) if report.total() > 0 => log::info!(
+                    "Purged {} expired rows: {} sessions, {} refresh tokens, {} OTP codes, {} OIDC login states, {} auth events anonymised",
+                    report.total(),
+                    report.sessions,
+                    report.refresh_tokens,
+                    report.otp_codes,
+                    report.oidc_login_states,
+                    report.auth_events
+                ),
+                Ok(_) => log::debug!("Purge found no expired rows"),
+                //* A failed run is retried on the next tick
+                Err(e) => log::error!("Failed to purge expired rows: {:?}", e),
+            }
+        }
+    });
+}
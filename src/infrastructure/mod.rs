@@ -1,4 +1,8 @@
 // Infrastructure layer - external services, databases, etc.
 pub mod database;
 pub mod email;
-pub mod email_template;
\ No newline at end of file
+pub mod email_template;
+pub mod maintenance;
+pub mod oidc;
+pub mod sms;
+pub mod storage;
\ No newline at end of file
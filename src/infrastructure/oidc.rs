@@ -0,0 +1,224 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use jsonwebtoken::{decode, decode_header, jwk::JwkSet, Algorithm, DecodingKey, Validation};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::config::OidcProviderSettings;
+
+/// Discovery documents and keys are refetched after this long.
+const METADATA_TTL: Duration = Duration::from_secs(3600);
+
+#[derive(Debug, thiserror::Error)]
+pub enum OidcError {
+    #[error("request to the provider failed: {0}")]
+    Http(#[from] reqwest::Error),
+    #[error("invalid authorization endpoint: {0}")]
+    Url(String),
+    #[error("token endpoint refused the code: {0}")]
+    TokenExchange(String),
+    #[error("invalid ID token: {0}")]
+    InvalidIdToken(String),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+struct CachedProvider {
+    metadata: ProviderMetadata,
+    jwks: JwkSet,
+    fetched_at: Instant,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: Option<String>,
+}
+
+/// Claims read from a verified ID token.
+#[derive(Debug, Clone, Deserialize)]
+pub struct IdTokenClaims {
+    pub sub: String,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub email_verified: bool,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+    pub name: Option<String>,
+    pub nonce: Option<String>,
+}
+
+/// S256 PKCE challenge for a code verifier.
+pub fn code_challenge(code_verifier: &str) -> String {
+    URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Talks to OpenID Connect providers, caching their discovery documents and signing keys.
+pub struct OidcClient {
+    http: reqwest::Client,
+    cache: Mutex<HashMap<String, Arc<CachedProvider>>>,
+}
+
+impl OidcClient {
+    pub fn new(timeout: Duration) -> Result<Self, OidcError> {
+        Ok(Self {
+            http: reqwest::Client::builder().timeout(timeout).build()?,
+            cache: Mutex::new(HashMap::new()),
+        })
+    }
+
+    async fn fetch_provider(&self, issuer_url: &str) -> Result<Arc<CachedProvider>, OidcError> {
+        let discovery_url = format!(
+            "{}/.well-known/openid-configuration",
+            issuer_url.trim_end_matches('/')
+        );
+        let metadata: ProviderMetadata = self
+            .http
+            .get(discovery_url)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let jwks: JwkSet = self
+            .http
+            .get(&metadata.jwks_uri)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+
+        let provider = Arc::new(CachedProvider {
+            metadata,
+            jwks,
+            fetched_at: Instant::now(),
+        });
+        self.cache
+            .lock()
+            .unwrap()
+            .insert(issuer_url.to_string(), provider.clone());
+        Ok(provider)
+    }
+
+    async fn provider(&self, issuer_url: &str) -> Result<Arc<CachedProvider>, OidcError> {
+        let cached = self.cache.lock().unwrap().get(issuer_url).cloned();
+        match cached {
+            Some(provider) if provider.fetched_at.elapsed() < METADATA_TTL => Ok(provider),
+            _ => self.fetch_provider(issuer_url).await,
+        }
+    }
+
+    /// URL to send the browser to, asking for an authorization code with PKCE.
+    pub async fn authorization_url(
+        &self,
+        settings: &OidcProviderSettings,
+        state: &str,
+        nonce: &str,
+        code_verifier: &str,
+    ) -> Result<String, OidcError> {
+        let provider = self.provider(&settings.issuer_url).await?;
+        let challenge = code_challenge(code_verifier);
+        let url = reqwest::Url::parse_with_params(
+            &provider.metadata.authorization_endpoint,
+            &[
+                ("response_type", "code"),
+                ("client_id", settings.client_id.as_str()),
+                ("redirect_uri", settings.redirect_uri.as_str()),
+                ("scope", settings.scopes.as_str()),
+                ("state", state),
+                ("nonce", nonce),
+                ("code_challenge", challenge.as_str()),
+                ("code_challenge_method", "S256"),
+            ],
+        )
+        .map_err(|e| OidcError::Url(e.to_string()))?;
+        Ok(url.to_string())
+    }
+
+    /// Redeems an authorization code and returns the claims of the verified ID token.
+    pub async fn exchange_code(
+        &self,
+        settings: &OidcProviderSettings,
+        code: &str,
+        code_verifier: &str,
+        nonce: &str,
+    ) -> Result<IdTokenClaims, OidcError> {
+        let provider = self.provider(&settings.issuer_url).await?;
+
+        let mut form = vec![
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", settings.redirect_uri.as_str()),
+            ("client_id", settings.client_id.as_str()),
+            ("code_verifier", code_verifier),
+        ];
+        if let Some(secret) = settings.client_secret.as_deref() {
+            form.push(("client_secret", secret));
+        }
+        let response = self
+            .http
+            .post(&provider.metadata.token_endpoint)
+            .form(&form)
+            .send()
+            .await?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(OidcError::TokenExchange(format!("{}: {}", status, body)));
+        }
+        let tokens: TokenResponse = response.json().await?;
+        let id_token = tokens
+            .id_token
+            .ok_or_else(|| OidcError::TokenExchange("response has no id_token".into()))?;
+
+        let claims = self.verify_id_token(settings, provider, &id_token).await?;
+        if claims.nonce.as_deref() != Some(nonce) {
+            return Err(OidcError::InvalidIdToken("nonce mismatch".into()));
+        }
+        Ok(claims)
+    }
+
+    async fn verify_id_token(
+        &self,
+        settings: &OidcProviderSettings,
+        mut provider: Arc<CachedProvider>,
+        id_token: &str,
+    ) -> Result<IdTokenClaims, OidcError> {
+        let header = decode_header(id_token).map_err(|e| OidcError::InvalidIdToken(e.to_string()))?;
+        //* Only asymmetric keys published in the JWKS are acceptable
+        if matches!(header.alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512) {
+            return Err(OidcError::InvalidIdToken("symmetric algorithms are not accepted".into()));
+        }
+        let kid = header
+            .kid
+            .ok_or_else(|| OidcError::InvalidIdToken("token has no kid".into()))?;
+
+        //* An unknown kid usually means the provider rotated its keys
+        if provider.jwks.find(&kid).is_none() {
+            provider = self.fetch_provider(&settings.issuer_url).await?;
+        }
+        let jwk = provider
+            .jwks
+            .find(&kid)
+            .ok_or_else(|| OidcError::InvalidIdToken(format!("unknown key {}", kid)))?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|e| OidcError::InvalidIdToken(e.to_string()))?;
+
+        let mut validation = Validation::new(header.alg);
+        validation.set_issuer(&[&provider.metadata.issuer]);
+        validation.set_audience(&[&settings.client_id]);
+        decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map(|data| data.claims)
+            .map_err(|e| OidcError::InvalidIdToken(e.to_string()))
+    }
+}
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::{fs::OpenOptions, io::AsyncWriteExt};
+
+use crate::config::{SmsDriver, SmsSettings};
+
+#[derive(Debug, thiserror::Error)]
+pub enum SmsError {
+    #[error("failed to write SMS: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("failed to encode SMS: {0}")]
+    Encode(#[from] serde_json::Error),
+}
+
+/// Delivers text messages. `to` is always an E.164 number such as `+6281234567890`.
+#[async_trait]
+pub trait SmsSender: Send + Sync {
+    async fn send(&self, to: &str, message: &str) -> Result<(), SmsError>;
+}
+
+/// Logs messages instead of sending them.
+pub struct LogSmsSender;
+
+#[async_trait]
+impl SmsSender for LogSmsSender {
+    async fn send(&self, to: &str, message: &str) -> Result<(), SmsError> {
+        log::info!("SMS to {}: {}", to, message);
+        Ok(())
+    }
+}
+
+/// Appends messages to a file as JSON lines, so tests can read the codes back.
+pub struct FileSmsSender {
+    path: String,
+}
+
+impl FileSmsSender {
+    pub fn new(path: impl Into<String>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+#[async_trait]
+impl SmsSender for FileSmsSender {
+    async fn send(&self, to: &str, message: &str) -> Result<(), SmsError> {
+        if let Some(parent) = std::path::Path::new(&self.path).parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut line = serde_json::to_string(&serde_json::json!({
+            "to": to,
+            "message": message,
+            "sent_at": chrono::Utc::now().to_rfc3339(),
+        }))?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .await?;
+        file.write_all(line.as_bytes()).await?;
+        Ok(())
+    }
+}
+
+pub fn create_sms_sender(settings: &SmsSettings) -> Arc<dyn SmsSender> {
+    match settings.driver {
+        SmsDriver::Log => Arc::new(LogSmsSender),
+        SmsDriver::File => Arc::new(FileSmsSender::new(settings.file_path.clone())),
+    }
+}
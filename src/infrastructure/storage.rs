@@ -0,0 +1,38 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::config::UploadSettings;
+
+/// Maps a public upload URL back to its file, refusing anything outside the upload directory.
+fn local_path(settings: &UploadSettings, url: &str) -> Option<PathBuf> {
+    let relative = url
+        .strip_prefix(settings.public_path.trim_end_matches('/'))?
+        .trim_start_matches('/');
+    let relative = Path::new(relative);
+    if relative
+        .components()
+        .any(|component| !matches!(component, Component::Normal(_)))
+    {
+        return None;
+    }
+    Some(Path::new(&settings.dir).join(relative))
+}
+
+/// Writes a file below the upload directory and returns its public URL.
+pub async fn store(settings: &UploadSettings, relative: &str, bytes: &[u8]) -> std::io::Result<String> {
+    let path = Path::new(&settings.dir).join(relative);
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent).await?;
+    }
+    tokio::fs::write(&path, bytes).await?;
+    Ok(format!("{}/{}", settings.public_path.trim_end_matches('/'), relative))
+}
+
+/// Deletes a previously stored file. URLs that don't point into the upload directory are ignored.
+pub async fn remove(settings: &UploadSettings, url: &str) {
+    let Some(path) = local_path(settings, url) else {
+        return;
+    };
+    if let Err(e) = tokio::fs::remove_file(&path).await {
+        log::warn!("Failed to remove upload {}: {:?}", path.display(), e);
+    }
+}
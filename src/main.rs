@@ -5,12 +5,22 @@ mod infrastructure;
 mod shared;
 
 use actix_cors::Cors;
+use actix_files::Files;
 use actix_web::middleware::Logger;
 use actix_web::web::Data;
 use actix_web::{App, HttpServer};
+use std::time::Duration;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use infrastructure::database::{init_pool, run_migrations, PostgresPool};
+use infrastructure::{
+    database::{init_pool, run_migrations, PostgresPool},
+    maintenance::spawn_purge_task,
+    oidc::OidcClient,
+    sms::create_sms_sender,
+};
+use shared::utils::{
+    permission_cache::PermissionCache, rate_limiter::RateLimiter, token_signing::TokenCodec,
+};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -25,6 +35,9 @@ async fn main() -> std::io::Result<()> {
     // Load configuration
     let settings = config::Settings::load().expect("Failed to load configuration");
 
+    // Build the token codec up front so a bad JWT configuration stops the boot
+    let token_codec = TokenCodec::from_settings(&settings.jwt).expect("Invalid JWT configuration");
+
     // Initialize database
     let pool = init_pool(&settings.database)
         .await
@@ -38,13 +51,50 @@ async fn main() -> std::io::Result<()> {
     // Create database infrastructure
     let db_pool = PostgresPool::new(pool);
 
+    // Clear out expired sessions, tokens and codes in the background
+    spawn_purge_task(db_pool.clone(), settings.maintenance.clone());
+
+    // Shared across workers so every worker counts against the same per-IP budget
+    let security = settings.security.clone();
+    let rate_limiter = Data::new(RateLimiter::new(
+        security.ip_max_requests,
+        Duration::from_secs(security.ip_window_seconds),
+    ));
+
+    let permission_cache = Data::new(PermissionCache::new(Duration::from_secs(
+        security.permission_cache_seconds,
+    )));
+    let password_settings = settings.password.clone();
+    let upload_settings = settings.upload.clone();
+    std::fs::create_dir_all(&upload_settings.dir)?;
+
+    let oidc_settings = settings.oidc.clone();
+    let oidc_client = Data::new(
+        OidcClient::new(Duration::from_secs(oidc_settings.http_timeout_seconds))
+            .expect("Failed to build the OIDC HTTP client"),
+    );
+
+    let sms_settings = settings.sms.clone();
+    let sms_sender = create_sms_sender(&sms_settings);
+
     // Start server
     HttpServer::new(move || {
         App::new()
             .wrap(Cors::default().allow_any_origin().allow_any_header().allow_any_method())
             .wrap(Logger::default())
             .app_data(Data::new(db_pool.clone()))
+            .app_data(Data::new(token_codec.clone()))
+            .app_data(Data::new(security.clone()))
+            .app_data(Data::new(password_settings.clone()))
+            .app_data(Data::new(upload_settings.clone()))
+            .app_data(Data::new(oidc_settings.clone()))
+            .app_data(oidc_client.clone())
+            .app_data(Data::new(sms_settings.clone()))
+            .app_data(Data::from(sms_sender.clone()))
+            .app_data(rate_limiter.clone())
+            .app_data(permission_cache.clone())
             .configure(api::register_urls)
+            .service(Files::new(&upload_settings.public_path, &upload_settings.dir))
     })
     .bind(settings.server.address())?
     .run()
@@ -0,0 +1,86 @@
+use std::io::Cursor;
+
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageDecoder, ImageFormat,
+    ImageReader, Limits,
+};
+
+use crate::config::UploadSettings;
+
+#[derive(Debug, thiserror::Error)]
+pub enum AvatarError {
+    #[error("unsupported image type")]
+    UnsupportedFormat,
+    #[error("image could not be processed: {0}")]
+    Image(#[from] image::ImageError),
+    #[error("image processing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// Square avatar and its thumbnail, both JPEG encoded.
+pub struct ProcessedAvatar {
+    pub avatar: Vec<u8>,
+    pub thumbnail: Vec<u8>,
+}
+
+/// Identifies the image type from its leading bytes; the client's content type is not trusted.
+pub fn detect_format(bytes: &[u8]) -> Option<ImageFormat> {
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(ImageFormat::Jpeg)
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(ImageFormat::Png)
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(ImageFormat::WebP)
+    } else {
+        None
+    }
+}
+
+fn encode_jpeg(image: &DynamicImage) -> Result<Vec<u8>, AvatarError> {
+    let mut buffer = Vec::new();
+    image
+        .to_rgb8()
+        .write_with_encoder(JpegEncoder::new_with_quality(&mut buffer, 85))?;
+    Ok(buffer)
+}
+
+fn process_blocking(bytes: &[u8], settings: &UploadSettings) -> Result<ProcessedAvatar, AvatarError> {
+    let format = detect_format(bytes).ok_or(AvatarError::UnsupportedFormat)?;
+
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(settings.max_dimension);
+    limits.max_image_height = Some(settings.max_dimension);
+    let mut reader = ImageReader::with_format(Cursor::new(bytes), format);
+    reader.limits(limits);
+
+    let mut decoder = reader.into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    //* Bake the EXIF rotation into the pixels; the metadata itself is dropped on re-encode
+    image.apply_orientation(orientation);
+
+    let avatar = image.resize_to_fill(settings.avatar_size, settings.avatar_size, FilterType::Lanczos3);
+    let thumbnail = avatar.resize_to_fill(
+        settings.thumbnail_size,
+        settings.thumbnail_size,
+        FilterType::Lanczos3,
+    );
+
+    Ok(ProcessedAvatar {
+        avatar: encode_jpeg(&avatar)?,
+        thumbnail: encode_jpeg(&thumbnail)?,
+    })
+}
+
+/// Decodes, crops and re-encodes an uploaded avatar on the blocking thread pool.
+pub async fn process_avatar(bytes: Vec<u8>, settings: &UploadSettings) -> Result<ProcessedAvatar, AvatarError> {
+    let settings = settings.clone();
+    tokio::task::spawn_blocking(move || process_blocking(&bytes, &settings)).await?
+}
+
+/// Public URL of the thumbnail stored next to an avatar.
+pub fn thumbnail_url(avatar_url: &str) -> Option<String> {
+    avatar_url
+        .strip_suffix(".jpg")
+        .map(|stem| format!("{}_thumb.jpg", stem))
+}
@@ -63,4 +63,18 @@ pub fn handle_error<T>(error: Box<dyn std::error::Error>, operation: &str) -> Ht
         "An internal error occurred".to_string(),
         Some("INTERNAL_ERROR".to_string())
     ))
-}
\ No newline at end of file
+}
+
+/// Names the users column behind a unique violation, e.g. when two registrations race.
+pub fn unique_violation_field(error: &sqlx::Error) -> Option<&'static str> {
+    match error {
+        sqlx::Error::Database(db_error) if db_error.is_unique_violation() => {
+            match db_error.constraint() {
+                Some("users_username_lower_key") => Some("Username"),
+                Some("users_email_lower_key") => Some("Email"),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
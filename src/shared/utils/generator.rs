@@ -1,15 +1,20 @@
-use rand::{thread_rng, Rng};
-use std::time::{SystemTime, UNIX_EPOCH};
+use rand::{distr::Alphanumeric, rngs::OsRng, Rng, TryRngCore};
+use sha2::{Digest, Sha256};
 
+/// Six-digit one-time code, drawn uniformly from the operating system's CSPRNG.
 pub fn generate_otp() -> u32 {
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .unwrap()
-        .as_secs()
-        / 30;
-    let mut rng = thread_rng();
-    let random_number: u32 = rng.gen_range(0..1000000);
-    let otp = (now as u32 ^ random_number) % 1000000;
+    OsRng.unwrap_err().random_range(0..1_000_000)
+}
 
-    otp
-}
\ No newline at end of file
+/// Random alphanumeric string of the given length, e.g. for refresh tokens and emailed links.
+pub fn generate_token(length: usize) -> String {
+    rand::rng()
+        .sample_iter(&Alphanumeric)
+        .take(length)
+        .map(char::from)
+        .collect()
+}
+
+pub fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
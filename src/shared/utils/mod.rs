@@ -1,4 +1,10 @@
+pub mod avatar;
 pub mod error_helpers;
 pub mod generator;
+pub mod password;
+pub mod permission_cache;
+pub mod phone;
+pub mod rate_limiter;
 pub mod standard_response;
-pub mod token_signing;
\ No newline at end of file
+pub mod token_signing;
+pub mod user_agent;
\ No newline at end of file
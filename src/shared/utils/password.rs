@@ -0,0 +1,219 @@
+use argon2::{
+    password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Algorithm, Argon2, Params, Version,
+};
+use rand::Rng;
+
+use crate::config::{PasswordAlgorithm, PasswordSettings};
+
+#[derive(Debug, thiserror::Error)]
+pub enum PasswordHashError {
+    #[error("bcrypt error: {0}")]
+    Bcrypt(#[from] bcrypt::BcryptError),
+    #[error("argon2 error: {0}")]
+    Argon2(String),
+    #[error("password hashing task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// Stored for accounts that have no password; it never verifies.
+pub const UNUSABLE_PASSWORD_HASH: &str = "!";
+
+/// Scheme of a stored hash, recognised from its prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HashScheme {
+    Bcrypt,
+    Argon2id,
+}
+
+fn detect_scheme(password_hash: &str) -> Option<HashScheme> {
+    if password_hash.starts_with("$argon2id$") {
+        Some(HashScheme::Argon2id)
+    } else if ["$2a$", "$2b$", "$2x$", "$2y$"]
+        .iter()
+        .any(|prefix| password_hash.starts_with(prefix))
+    {
+        Some(HashScheme::Bcrypt)
+    } else {
+        None
+    }
+}
+
+fn argon2_params(settings: &PasswordSettings) -> Result<Params, PasswordHashError> {
+    Params::new(
+        settings.argon2_memory_kib,
+        settings.argon2_iterations,
+        settings.argon2_parallelism,
+        None,
+    )
+    .map_err(|e| PasswordHashError::Argon2(e.to_string()))
+}
+
+fn hash_blocking(password: &str, settings: &PasswordSettings) -> Result<String, PasswordHashError> {
+    match settings.algorithm {
+        PasswordAlgorithm::Bcrypt => Ok(bcrypt::hash(password, settings.bcrypt_cost)?),
+        PasswordAlgorithm::Argon2id => {
+            let salt_bytes: [u8; 16] = rand::rng().random();
+            let salt = SaltString::encode_b64(&salt_bytes)
+                .map_err(|e| PasswordHashError::Argon2(e.to_string()))?;
+            Argon2::new(Algorithm::Argon2id, Version::V0x13, argon2_params(settings)?)
+                .hash_password(password.as_bytes(), &salt)
+                .map(|hash| hash.to_string())
+                .map_err(|e| PasswordHashError::Argon2(e.to_string()))
+        }
+    }
+}
+
+fn verify_blocking(password: &str, password_hash: &str) -> Result<bool, PasswordHashError> {
+    match detect_scheme(password_hash) {
+        Some(HashScheme::Bcrypt) => Ok(bcrypt::verify(password, password_hash)?),
+        Some(HashScheme::Argon2id) => {
+            let parsed = PasswordHash::new(password_hash)
+                .map_err(|e| PasswordHashError::Argon2(e.to_string()))?;
+            //* Parameters come from the hash itself
+            Ok(Argon2::default()
+                .verify_password(password.as_bytes(), &parsed)
+                .is_ok())
+        }
+        None => {
+            log::warn!("Stored password hash uses an unsupported scheme");
+            Ok(false)
+        }
+    }
+}
+
+/// Hashes the password with the configured scheme on the blocking thread pool.
+pub async fn hash_password(password: &str, settings: &PasswordSettings) -> Result<String, PasswordHashError> {
+    let password = password.to_string();
+    let settings = settings.clone();
+    tokio::task::spawn_blocking(move || hash_blocking(&password, &settings)).await?
+}
+
+/// Checks a password against a bcrypt or Argon2id hash on the blocking thread pool.
+pub async fn verify_password(password: &str, password_hash: &str) -> Result<bool, PasswordHashError> {
+    let password = password.to_string();
+    let password_hash = password_hash.to_string();
+    tokio::task::spawn_blocking(move || verify_blocking(&password, &password_hash)).await?
+}
+
+/// Whether a stored hash was made with a different scheme or parameters than the current settings.
+pub fn needs_rehash(password_hash: &str, settings: &PasswordSettings) -> bool {
+    match (detect_scheme(password_hash), settings.algorithm) {
+        (Some(HashScheme::Bcrypt), PasswordAlgorithm::Bcrypt) => {
+            //* bcrypt hashes look like `$2b$10$<salt+hash>`
+            let cost = password_hash
+                .split('$')
+                .nth(2)
+                .and_then(|cost| cost.parse::<u32>().ok());
+            cost != Some(settings.bcrypt_cost)
+        }
+        (Some(HashScheme::Argon2id), PasswordAlgorithm::Argon2id) => {
+            let params = PasswordHash::new(password_hash)
+                .ok()
+                .and_then(|parsed| Params::try_from(&parsed).ok());
+            match params {
+                Some(params) => {
+                    params.m_cost() != settings.argon2_memory_kib
+                        || params.t_cost() != settings.argon2_iterations
+                        || params.p_cost() != settings.argon2_parallelism
+                }
+                None => true,
+            }
+        }
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //* Cheap parameters; the defaults would make the tests slow
+    fn bcrypt_settings() -> PasswordSettings {
+        PasswordSettings {
+            algorithm: PasswordAlgorithm::Bcrypt,
+            bcrypt_cost: 4,
+            ..Default::default()
+        }
+    }
+
+    fn argon2_settings() -> PasswordSettings {
+        PasswordSettings {
+            algorithm: PasswordAlgorithm::Argon2id,
+            argon2_memory_kib: 1024,
+            argon2_iterations: 1,
+            argon2_parallelism: 1,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn detects_the_scheme_from_the_prefix() {
+        let bcrypt_hash = hash_blocking("Kopi-Tubruk7", &bcrypt_settings()).unwrap();
+        let argon2_hash = hash_blocking("Kopi-Tubruk7", &argon2_settings()).unwrap();
+        assert_eq!(detect_scheme(&bcrypt_hash), Some(HashScheme::Bcrypt));
+        assert_eq!(detect_scheme(&argon2_hash), Some(HashScheme::Argon2id));
+        assert_eq!(detect_scheme("$2y$10$abcdefghijklmnopqrstuv"), Some(HashScheme::Bcrypt));
+        assert_eq!(detect_scheme("$argon2i$v=19$m=1024,t=1,p=1$c2FsdA$aGFzaA"), None);
+        assert_eq!(detect_scheme(UNUSABLE_PASSWORD_HASH), None);
+    }
+
+    #[test]
+    fn verifies_both_schemes() {
+        for settings in [bcrypt_settings(), argon2_settings()] {
+            let password_hash = hash_blocking("Kopi-Tubruk7", &settings).unwrap();
+            assert!(verify_blocking("Kopi-Tubruk7", &password_hash).unwrap());
+            assert!(!verify_blocking("kopi-tubruk7", &password_hash).unwrap());
+        }
+    }
+
+    #[test]
+    fn unusable_hash_never_verifies() {
+        assert!(!verify_blocking("", UNUSABLE_PASSWORD_HASH).unwrap());
+        assert!(!verify_blocking("!", UNUSABLE_PASSWORD_HASH).unwrap());
+    }
+
+    #[test]
+    fn current_hashes_do_not_need_a_rehash() {
+        for settings in [bcrypt_settings(), argon2_settings()] {
+            let password_hash = hash_blocking("Kopi-Tubruk7", &settings).unwrap();
+            assert!(!needs_rehash(&password_hash, &settings));
+        }
+    }
+
+    #[test]
+    fn rehashes_when_the_scheme_changes() {
+        let bcrypt_hash = hash_blocking("Kopi-Tubruk7", &bcrypt_settings()).unwrap();
+        let argon2_hash = hash_blocking("Kopi-Tubruk7", &argon2_settings()).unwrap();
+        assert!(needs_rehash(&bcrypt_hash, &argon2_settings()));
+        assert!(needs_rehash(&argon2_hash, &bcrypt_settings()));
+    }
+
+    #[test]
+    fn rehashes_when_the_parameters_change() {
+        let bcrypt_hash = hash_blocking("Kopi-Tubruk7", &bcrypt_settings()).unwrap();
+        let costlier = PasswordSettings {
+            bcrypt_cost: 5,
+            ..bcrypt_settings()
+        };
+        assert!(needs_rehash(&bcrypt_hash, &costlier));
+
+        let argon2_hash = hash_blocking("Kopi-Tubruk7", &argon2_settings()).unwrap();
+        let more_memory = PasswordSettings {
+            argon2_memory_kib: 2048,
+            ..argon2_settings()
+        };
+        let more_iterations = PasswordSettings {
+            argon2_iterations: 2,
+            ..argon2_settings()
+        };
+        assert!(needs_rehash(&argon2_hash, &more_memory));
+        assert!(needs_rehash(&argon2_hash, &more_iterations));
+    }
+
+    #[test]
+    fn rehashes_unrecognised_hashes() {
+        assert!(needs_rehash(UNUSABLE_PASSWORD_HASH, &argon2_settings()));
+        assert!(needs_rehash("$argon2id$garbage", &argon2_settings()));
+    }
+}
@@ -0,0 +1,54 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, RwLock},
+    time::{Duration, Instant},
+};
+
+struct CachedPermissions {
+    loaded_at: Instant,
+    permissions: Arc<HashSet<String>>,
+}
+
+/// Permission names per role, shared by all workers through app data.
+///
+/// Admin changes invalidate the affected role right away on this instance;
+/// other instances pick them up once their entry is older than the TTL.
+pub struct PermissionCache {
+    ttl: Duration,
+    roles: RwLock<HashMap<i32, CachedPermissions>>,
+}
+
+impl PermissionCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            roles: RwLock::new(HashMap::new()),
+        }
+    }
+
+    pub fn get(&self, role_id: i32) -> Option<Arc<HashSet<String>>> {
+        let roles = self.roles.read().unwrap_or_else(|e| e.into_inner());
+        roles
+            .get(&role_id)
+            .filter(|cached| cached.loaded_at.elapsed() < self.ttl)
+            .map(|cached| Arc::clone(&cached.permissions))
+    }
+
+    pub fn insert(&self, role_id: i32, permissions: Vec<String>) -> Arc<HashSet<String>> {
+        let permissions = Arc::new(permissions.into_iter().collect::<HashSet<_>>());
+        let mut roles = self.roles.write().unwrap_or_else(|e| e.into_inner());
+        roles.insert(
+            role_id,
+            CachedPermissions {
+                loaded_at: Instant::now(),
+                permissions: Arc::clone(&permissions),
+            },
+        );
+        permissions
+    }
+
+    pub fn invalidate(&self, role_id: i32) {
+        let mut roles = self.roles.write().unwrap_or_else(|e| e.into_inner());
+        roles.remove(&role_id);
+    }
+}
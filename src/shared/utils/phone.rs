@@ -0,0 +1,81 @@
+/// Country code assumed for numbers written in the local trunk format, e.g. `0812...`.
+const DEFAULT_COUNTRY_CODE: &str = "62";
+
+/// Normalises a phone number to E.164, e.g. `0812-3456-7890` and `62812...` become
+/// `+6281234567890`. Returns `None` if the result isn't a plausible E.164 number.
+pub fn normalize_phone(input: &str) -> Option<String> {
+    let trimmed = input.trim();
+    let has_plus = trimmed.starts_with('+');
+    let digits: String = trimmed
+        .chars()
+        .filter(|c| !matches!(c, ' ' | '-' | '.' | '(' | ')'))
+        .skip(usize::from(has_plus))
+        .collect();
+    if digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    let international = if has_plus {
+        digits
+    } else if let Some(local) = digits.strip_prefix('0') {
+        format!("{}{}", DEFAULT_COUNTRY_CODE, local)
+    } else if digits.starts_with(DEFAULT_COUNTRY_CODE) {
+        digits
+    } else {
+        return None;
+    };
+
+    //* E.164 allows up to 15 digits; `+620...` is a trunk prefix that was left in
+    let valid = (8..=15).contains(&international.len())
+        && !international.starts_with('0')
+        && !international
+            .strip_prefix(DEFAULT_COUNTRY_CODE)
+            .is_some_and(|national| national.starts_with('0'));
+    valid.then(|| format!("+{}", international))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalises_indonesian_local_numbers() {
+        assert_eq!(normalize_phone("081234567890").as_deref(), Some("+6281234567890"));
+        assert_eq!(normalize_phone("0812-3456-7890").as_deref(), Some("+6281234567890"));
+        assert_eq!(normalize_phone(" (0812) 3456.7890 ").as_deref(), Some("+6281234567890"));
+    }
+
+    #[test]
+    fn accepts_numbers_with_the_country_code() {
+        assert_eq!(normalize_phone("6281234567890").as_deref(), Some("+6281234567890"));
+        assert_eq!(normalize_phone("+62 812 3456 7890").as_deref(), Some("+6281234567890"));
+    }
+
+    #[test]
+    fn keeps_other_countries_when_written_with_a_plus() {
+        assert_eq!(normalize_phone("+65 6123 4567").as_deref(), Some("+6561234567"));
+        //* Without the plus there's no telling which country it is
+        assert_eq!(normalize_phone("6561234567"), None);
+    }
+
+    #[test]
+    fn rejects_a_trunk_prefix_left_after_the_country_code() {
+        assert_eq!(normalize_phone("+62081234567890"), None);
+        assert_eq!(normalize_phone("62081234567890"), None);
+    }
+
+    #[test]
+    fn rejects_implausible_lengths() {
+        assert_eq!(normalize_phone("0812"), None);
+        assert_eq!(normalize_phone("+1234567890123456"), None);
+    }
+
+    #[test]
+    fn rejects_anything_but_digits_and_separators() {
+        assert_eq!(normalize_phone(""), None);
+        assert_eq!(normalize_phone("+"), None);
+        assert_eq!(normalize_phone("0812abc67890"), None);
+        assert_eq!(normalize_phone("++6281234567890"), None);
+        assert_eq!(normalize_phone("+0812345678"), None);
+    }
+}
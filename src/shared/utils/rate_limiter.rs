@@ -0,0 +1,89 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// Entries are swept once the map grows past this many keys.
+const SWEEP_THRESHOLD: usize = 10_000;
+
+struct Window {
+    started_at: Instant,
+    count: u32,
+}
+
+/// In-memory fixed-window request counter, shared by all workers through app data.
+pub struct RateLimiter {
+    max_requests: u32,
+    window: Duration,
+    windows: Mutex<HashMap<String, Window>>,
+}
+
+impl RateLimiter {
+    pub fn new(max_requests: u32, window: Duration) -> Self {
+        Self {
+            max_requests,
+            window,
+            windows: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a request for `key`. Returns the seconds to wait when the limit is exceeded.
+    pub fn check(&self, key: &str) -> Result<(), u64> {
+        let now = Instant::now();
+        let mut windows = self.windows.lock().unwrap_or_else(|e| e.into_inner());
+
+        if windows.len() >= SWEEP_THRESHOLD {
+            windows.retain(|_, w| now.duration_since(w.started_at) < self.window);
+        }
+
+        let window = windows.entry(key.to_string()).or_insert(Window {
+            started_at: now,
+            count: 0,
+        });
+        if now.duration_since(window.started_at) >= self.window {
+            window.started_at = now;
+            window.count = 0;
+        }
+
+        if window.count >= self.max_requests {
+            let remaining = self.window.saturating_sub(now.duration_since(window.started_at));
+            return Err((remaining.as_millis() as u64).div_ceil(1000).max(1));
+        }
+        window.count += 1;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_up_to_the_limit_within_a_window() {
+        let limiter = RateLimiter::new(3, Duration::from_secs(60));
+        for _ in 0..3 {
+            assert_eq!(limiter.check("login:1.2.3.4"), Ok(()));
+        }
+        let retry_after = limiter.check("login:1.2.3.4").unwrap_err();
+        assert!((1..=60).contains(&retry_after));
+    }
+
+    #[test]
+    fn counts_keys_separately() {
+        let limiter = RateLimiter::new(1, Duration::from_secs(60));
+        assert_eq!(limiter.check("login:1.2.3.4"), Ok(()));
+        assert!(limiter.check("login:1.2.3.4").is_err());
+        assert_eq!(limiter.check("login:5.6.7.8"), Ok(()));
+        assert_eq!(limiter.check("otp:1.2.3.4"), Ok(()));
+    }
+
+    #[test]
+    fn starts_a_fresh_window_once_the_old_one_ends() {
+        let limiter = RateLimiter::new(1, Duration::from_millis(20));
+        assert_eq!(limiter.check("login:1.2.3.4"), Ok(()));
+        assert_eq!(limiter.check("login:1.2.3.4"), Err(1));
+        std::thread::sleep(Duration::from_millis(30));
+        assert_eq!(limiter.check("login:1.2.3.4"), Ok(()));
+    }
+}
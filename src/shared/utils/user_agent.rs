@@ -0,0 +1,94 @@
+/// Browsers in match order; Chromium derivatives carry `Chrome/` too, and Chrome carries `Safari/`.
+const BROWSERS: &[(&str, &str)] = &[
+    ("Edg/", "Edge"),
+    ("OPR/", "Opera"),
+    ("SamsungBrowser/", "Samsung Internet"),
+    ("Firefox/", "Firefox"),
+    ("FxiOS/", "Firefox"),
+    ("CriOS/", "Chrome"),
+    ("Chrome/", "Chrome"),
+    ("Safari/", "Safari"),
+    ("okhttp/", "Android app"),
+    ("CFNetwork/", "iOS app"),
+    ("curl/", "curl"),
+];
+
+/// Operating systems in match order; Android reports Linux and iOS reports "like Mac OS X".
+const OPERATING_SYSTEMS: &[(&str, &str)] = &[
+    ("Windows", "Windows"),
+    ("Android", "Android"),
+    ("iPhone", "iOS"),
+    ("iPad", "iPadOS"),
+    ("CrOS", "ChromeOS"),
+    ("Mac OS X", "macOS"),
+    ("Linux", "Linux"),
+];
+
+/// A short, human-readable device description like "Chrome on Windows" for emails and
+/// activity views. Only names from the lists above are returned, never the raw header.
+pub fn describe_user_agent(user_agent: Option<&str>) -> String {
+    let user_agent = user_agent.unwrap_or_default();
+    let browser = BROWSERS
+        .iter()
+        .find(|(token, _)| user_agent.contains(token))
+        .map(|(_, name)| *name);
+    let os = OPERATING_SYSTEMS
+        .iter()
+        .find(|(token, _)| user_agent.contains(token))
+        .map(|(_, name)| *name);
+    match (browser, os) {
+        (Some(browser), Some(os)) => format!("{} on {}", browser, os),
+        (Some(browser), None) => browser.to_string(),
+        (None, Some(os)) => format!("Unknown browser on {}", os),
+        (None, None) => "Unknown device".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHROME_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36";
+    const EDGE_WINDOWS: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Safari/537.36 Edg/126.0.0.0";
+    const FIREFOX_LINUX: &str =
+        "Mozilla/5.0 (X11; Linux x86_64; rv:127.0) Gecko/20100101 Firefox/127.0";
+    const CHROME_ANDROID: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/126.0.0.0 Mobile Safari/537.36";
+    const SAFARI_IPHONE: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_5 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Mobile/15E148 Safari/604.1";
+    const SAFARI_MAC: &str = "Mozilla/5.0 (Macintosh; Intel Mac OS X 14_5) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.5 Safari/605.1.15";
+
+    #[test]
+    fn names_common_browsers_and_systems() {
+        assert_eq!(
+            describe_user_agent(Some(CHROME_WINDOWS)),
+            "Chrome on Windows"
+        );
+        assert_eq!(describe_user_agent(Some(FIREFOX_LINUX)), "Firefox on Linux");
+        assert_eq!(describe_user_agent(Some(SAFARI_MAC)), "Safari on macOS");
+    }
+
+    #[test]
+    fn prefers_the_more_specific_token() {
+        //* Edge also sends Chrome/, Android also sends Linux, iOS also sends Mac OS X
+        assert_eq!(describe_user_agent(Some(EDGE_WINDOWS)), "Edge on Windows");
+        assert_eq!(
+            describe_user_agent(Some(CHROME_ANDROID)),
+            "Chrome on Android"
+        );
+        assert_eq!(describe_user_agent(Some(SAFARI_IPHONE)), "Safari on iOS");
+    }
+
+    #[test]
+    fn falls_back_when_parts_are_unknown() {
+        assert_eq!(describe_user_agent(Some("curl/8.5.0")), "curl");
+        assert_eq!(
+            describe_user_agent(Some("SomeBot (Windows)")),
+            "Unknown browser on Windows"
+        );
+        assert_eq!(
+            describe_user_agent(Some("<script>alert(1)</script>")),
+            "Unknown device"
+        );
+        assert_eq!(describe_user_agent(Some("")), "Unknown device");
+        assert_eq!(describe_user_agent(None), "Unknown device");
+    }
+}